@@ -0,0 +1,114 @@
+/// Embedded ICAO aircraft type designator dataset
+use crate::client::FullLiveFlightData;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+const AIRCRAFT_TYPES_CSV: &str = include_str!("../data/aircraft_types.csv");
+
+/// Propulsion of an aircraft type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EngineType {
+    Jet,
+    Turboprop,
+    Piston,
+    Electric,
+}
+
+/// ICAO wake turbulence category
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum WakeCategory {
+    /// Up to 7 000 kg maximum take-off mass
+    Light,
+    /// Between 7 000 kg and 136 000 kg
+    Medium,
+    /// 136 000 kg or more
+    Heavy,
+    /// The A380 and An-225
+    Super,
+}
+
+/// Details of an ICAO type designator
+#[derive(Debug, Clone, PartialEq)]
+pub struct AircraftTypeInfo {
+    pub designator: &'static str,
+    pub manufacturer: &'static str,
+    pub model: &'static str,
+    pub engine_count: u8,
+    pub engine_type: EngineType,
+    pub wake_category: WakeCategory,
+    /// Typical cruise true airspeed in knots
+    pub cruise_speed_kt: u16,
+}
+
+impl AircraftTypeInfo {
+    /// Manufacturer and model, e.g. `Boeing 737 MAX 8`
+    pub fn full_name(&self) -> String {
+        format!("{} {}", self.manufacturer, self.model)
+    }
+}
+
+fn database() -> &'static HashMap<&'static str, AircraftTypeInfo> {
+    static DATABASE: OnceLock<HashMap<&'static str, AircraftTypeInfo>> = OnceLock::new();
+    DATABASE.get_or_init(|| {
+        AIRCRAFT_TYPES_CSV
+            .lines()
+            .skip(1)
+            .filter_map(parse_line)
+            .map(|info| (info.designator, info))
+            .collect()
+    })
+}
+
+fn parse_line(line: &'static str) -> Option<AircraftTypeInfo> {
+    let fields: Vec<&'static str> = line.split(',').map(str::trim).collect();
+    let [designator, manufacturer, model, engine_count, engine_type, wake_category, cruise_speed] =
+        fields.as_slice()
+    else {
+        return None;
+    };
+
+    Some(AircraftTypeInfo {
+        designator,
+        manufacturer,
+        model,
+        engine_count: engine_count.parse().ok()?,
+        engine_type: match *engine_type {
+            "J" => EngineType::Jet,
+            "T" => EngineType::Turboprop,
+            "P" => EngineType::Piston,
+            "E" => EngineType::Electric,
+            _ => return None,
+        },
+        wake_category: match *wake_category {
+            "L" => WakeCategory::Light,
+            "M" => WakeCategory::Medium,
+            "H" => WakeCategory::Heavy,
+            "J" => WakeCategory::Super,
+            _ => return None,
+        },
+        cruise_speed_kt: cruise_speed.parse().ok()?,
+    })
+}
+
+/// Looks up an ICAO type designator, ignoring case.
+/// # Arguments
+///   * `designator` - Type designator such as `B38M`
+/// # Returns
+///   The `AircraftTypeInfo` when the type is in the dataset
+pub fn aircraft_type(designator: &str) -> Option<&'static AircraftTypeInfo> {
+    database().get(designator.trim().to_ascii_uppercase().as_str())
+}
+
+/// Every aircraft type in the dataset, in no particular order
+pub fn aircraft_types() -> impl Iterator<Item = &'static AircraftTypeInfo> {
+    database().values()
+}
+
+impl FullLiveFlightData {
+    /// Details of the aircraft type from the embedded dataset
+    /// # Returns
+    ///   The `AircraftTypeInfo` when `type_field` is a known designator
+    pub fn aircraft_type_info(&self) -> Option<&'static AircraftTypeInfo> {
+        aircraft_type(&self.type_field)
+    }
+}
@@ -0,0 +1,291 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use clap::Parser;
+use dotenv::dotenv;
+use flightradar24_api::cache::{CachePolicy, DiskCache, EndpointKind, MemoryCache, ResponseCache};
+use flightradar24_api::client::{FlightRadarClient, ResponseSource};
+use flightradar24_api::rate_limit::RateLimiter;
+use serde::Serialize;
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+const SANDBOX_URL: &str = "https://fr24api.flightradar24.com/api/sandbox/";
+
+/// Local proxy exposing the FlightRadar24 API paths with caching, rate limiting
+/// and per team accounting, using a single shared API key
+#[derive(Parser)]
+#[command(name = "fr24-gateway", version)]
+struct Cli {
+    /// Shared API key, read from `API_KEY` (or a `.env` file) when not given
+    #[arg(long, env = "API_KEY", hide_env_values = true)]
+    api_key: String,
+
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1:8024")]
+    listen: String,
+
+    /// Forward requests to the FlightRadar24 sandbox
+    #[arg(long, conflicts_with = "base_url")]
+    sandbox: bool,
+
+    /// Override the upstream API base URL
+    #[arg(long)]
+    base_url: Option<String>,
+
+    /// Team access token as `NAME=TOKEN`, repeatable. Without teams every
+    /// request is accounted to `default` and no token is required
+    #[arg(long = "team", value_parser = parse_team)]
+    teams: Vec<(String, String)>,
+
+    /// Token allowed to read the usage of every team at `/gateway/usage`,
+    /// other tokens only see their own team
+    #[arg(long, env = "FR24_GATEWAY_ADMIN_TOKEN", hide_env_values = true)]
+    admin_token: Option<String>,
+
+    /// Upstream requests allowed per minute across all teams
+    #[arg(long, default_value_t = 60)]
+    rate_limit: u32,
+
+    /// Requests allowed per minute for each team, cache hits included
+    #[arg(long)]
+    team_rate_limit: Option<u32>,
+
+    /// Keep cached responses on disk instead of in memory
+    #[arg(long)]
+    cache_dir: Option<String>,
+
+    /// Responses kept by the in-memory cache
+    #[arg(long, default_value_t = 10_000)]
+    cache_size: usize,
+
+    /// Worker threads handling requests
+    #[arg(long, default_value_t = 4)]
+    threads: usize,
+}
+
+fn parse_team(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((name, token)) if !name.is_empty() && !token.is_empty() => {
+            Ok((name.to_string(), token.to_string()))
+        }
+        _ => Err("expected NAME=TOKEN".to_string()),
+    }
+}
+
+/// Usage accounted to one team
+#[derive(Debug, Clone, Default, Serialize)]
+struct TeamUsage {
+    /// Requests received from the team
+    requests: u64,
+    /// Requests rejected by the team rate limit
+    rate_limited: u64,
+    /// Requests that failed upstream
+    errors: u64,
+    /// Requests answered from the shared cache, not charged by FR24
+    cache_hits: u64,
+    /// Results fetched from the API, the unit FR24 credits are charged on
+    results: u64,
+}
+
+/// Results FR24 charges for a successful response of an endpoint
+fn charged_results(kind: Option<EndpointKind>, body: &Value) -> u64 {
+    match kind {
+        Some(EndpointKind::Live | EndpointKind::Historic) => body
+            .get("data")
+            .and_then(|d| d.as_array())
+            .map_or(0, |d| d.len() as u64),
+        Some(EndpointKind::FlightTracks) => body.as_array().map_or(0, |f| f.len() as u64),
+        Some(EndpointKind::Static) => 1,
+        Some(EndpointKind::Usage) | None => 0,
+    }
+}
+
+struct Gateway {
+    client: FlightRadarClient,
+    /// Team name per access token
+    teams: HashMap<String, String>,
+    admin_token: Option<String>,
+    team_limiters: HashMap<String, RateLimiter>,
+    usage: Mutex<BTreeMap<String, TeamUsage>>,
+}
+
+fn main() {
+    dotenv().ok();
+    let cli = Cli::parse();
+
+    let mut client = FlightRadarClient::new(cli.api_key);
+    if cli.sandbox {
+        client.update_base_url(SANDBOX_URL.to_string());
+    } else if let Some(base_url) = cli.base_url {
+        client.update_base_url(base_url);
+    }
+
+    let cache: Box<dyn ResponseCache> = match &cli.cache_dir {
+        Some(dir) => match DiskCache::new(dir) {
+            Ok(cache) => Box::new(cache),
+            Err(e) => {
+                eprintln!("Cache directory {}: {}", dir, e);
+                std::process::exit(1);
+            }
+        },
+        None => Box::new(MemoryCache::new(cli.cache_size)),
+    };
+    client.set_cache(cache, CachePolicy::default());
+    client.set_rate_limit(RateLimiter::new(cli.rate_limit, Duration::from_secs(60)));
+
+    let team_names: Vec<String> = if cli.teams.is_empty() {
+        vec!["default".to_string()]
+    } else {
+        cli.teams.iter().map(|(name, _)| name.to_string()).collect()
+    };
+    let gateway = Arc::new(Gateway {
+        client,
+        teams: cli
+            .teams
+            .iter()
+            .map(|(name, token)| (token.to_string(), name.to_string()))
+            .collect(),
+        admin_token: cli.admin_token,
+        team_limiters: match cli.team_rate_limit {
+            Some(limit) => team_names
+                .iter()
+                .map(|name| {
+                    (
+                        name.to_string(),
+                        RateLimiter::new(limit, Duration::from_secs(60)),
+                    )
+                })
+                .collect(),
+            None => HashMap::new(),
+        },
+        usage: Mutex::new(BTreeMap::new()),
+    });
+
+    let server = match Server::http(&cli.listen) {
+        Ok(server) => Arc::new(server),
+        Err(e) => {
+            eprintln!("Listening on {}: {}", cli.listen, e);
+            std::process::exit(1);
+        }
+    };
+    println!("fr24-gateway listening on http://{}", cli.listen);
+
+    let workers: Vec<_> = (0..cli.threads.max(1))
+        .map(|_| {
+            let server = Arc::clone(&server);
+            let gateway = Arc::clone(&gateway);
+            thread::spawn(move || {
+                while let Ok(request) = server.recv() {
+                    gateway.handle(request);
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        let _ = worker.join();
+    }
+}
+
+impl Gateway {
+    fn handle(&self, request: Request) {
+        let (status, body) = self.route(&request);
+        let response = Response::from_string(body.to_string())
+            .with_status_code(status)
+            .with_header(
+                Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+                    .expect("static header"),
+            );
+        if let Err(e) = request.respond(response) {
+            eprintln!("Responding to client: {}", e);
+        }
+    }
+
+    fn route(&self, request: &Request) -> (u16, Value) {
+        if *request.method() != Method::Get {
+            return (405, json!({ "message": "Only GET is supported" }));
+        }
+
+        let url = request.url().to_string();
+        let admin =
+            self.admin_token.is_some() && bearer_token(request) == self.admin_token.as_deref();
+        if url == "/gateway/usage" && admin {
+            let usage = self.usage.lock().unwrap_or_else(|e| e.into_inner());
+            return (200, json!(*usage));
+        }
+
+        let team = match self.team_for(request) {
+            Some(team) => team,
+            None => return (401, json!({ "message": "Unknown team token" })),
+        };
+
+        if url == "/gateway/usage" {
+            let usage = self.usage.lock().unwrap_or_else(|e| e.into_inner());
+            let own = usage.get(&team).cloned().unwrap_or_default();
+            return (200, json!({ team: own }));
+        }
+        let Some(path) = url.strip_prefix("/api/") else {
+            return (404, json!({ "message": "Unknown path" }));
+        };
+
+        self.account(&team, |usage| usage.requests += 1);
+        if let Some(limiter) = self.team_limiters.get(&team) {
+            if let Err(wait) = limiter.try_acquire() {
+                self.account(&team, |usage| usage.rate_limited += 1);
+                return (
+                    429,
+                    json!({
+                        "message": "Team rate limit exceeded",
+                        "retry_after_secs": wait.as_secs_f64().ceil(),
+                    }),
+                );
+            }
+        }
+
+        let upstream = format!("{}{}", self.client.base_url(), path);
+        let kind = EndpointKind::from_url(&upstream, self.client.base_url());
+        match self.client.query_endpoint_with_source(upstream) {
+            Ok((status, text, source)) => {
+                let body: Value = serde_json::from_str(&text).unwrap_or(Value::String(text));
+                if !(200..300).contains(&status) {
+                    self.account(&team, |usage| usage.errors += 1);
+                } else if source == ResponseSource::Network {
+                    let results = charged_results(kind, &body);
+                    self.account(&team, |usage| usage.results += results);
+                } else {
+                    self.account(&team, |usage| usage.cache_hits += 1);
+                }
+                (status, body)
+            }
+            Err(e) => {
+                self.account(&team, |usage| usage.errors += 1);
+                (502, json!({ "message": e.to_string() }))
+            }
+        }
+    }
+
+    /// Team owning the bearer token of a request
+    fn team_for(&self, request: &Request) -> Option<String> {
+        if self.teams.is_empty() {
+            return Some("default".to_string());
+        }
+
+        self.teams.get(bearer_token(request)?).cloned()
+    }
+
+    fn account<F: FnOnce(&mut TeamUsage)>(&self, team: &str, update: F) {
+        let mut usage = self.usage.lock().unwrap_or_else(|e| e.into_inner());
+        update(usage.entry(team.to_string()).or_default());
+    }
+}
+
+/// Token of the `Authorization: Bearer` header
+fn bearer_token(request: &Request) -> Option<&str> {
+    let header = request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv("Authorization"))?;
+    Some(header.value.as_str().strip_prefix("Bearer ")?.trim())
+}
@@ -0,0 +1,629 @@
+use std::process::ExitCode;
+#[cfg(feature = "tui")]
+use std::time::Duration;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use dotenv::dotenv;
+use flightradar24_api::client::*;
+use flightradar24_api::error::FlightRadarError;
+use flightradar24_api::export::flights_to_geojson;
+use flightradar24_api::filter::{AircraftTypeFilter, AirportFilter, RouteFilter};
+use serde_json::{json, Value};
+
+#[cfg(feature = "tui")]
+mod watch;
+
+const SANDBOX_URL: &str = "https://fr24api.flightradar24.com/api/sandbox/";
+
+/// Command line access to the FlightRadar24 API
+#[derive(Parser)]
+#[command(name = "fr24", version)]
+struct Cli {
+    /// API key, read from `API_KEY` (or a `.env` file) when not given
+    #[arg(long, env = "API_KEY", hide_env_values = true)]
+    api_key: String,
+
+    /// Send requests to the FlightRadar24 sandbox
+    #[arg(long, global = true, conflicts_with = "base_url")]
+    sandbox: bool,
+
+    /// Override the API base URL
+    #[arg(long, global = true)]
+    base_url: Option<String>,
+
+    /// Output format
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Table)]
+    format: OutputFormat,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum OutputFormat {
+    Table,
+    Json,
+    Csv,
+    Geojson,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Live flight positions
+    Live {
+        /// Use the light endpoint
+        #[arg(long)]
+        light: bool,
+        #[command(flatten)]
+        query: QueryArgs,
+    },
+    /// Historic flight positions at a Unix timestamp
+    Historic {
+        timestamp: u64,
+        /// Use the light endpoint
+        #[arg(long)]
+        light: bool,
+        #[command(flatten)]
+        query: QueryArgs,
+    },
+    /// Positions recorded for a flight
+    Tracks {
+        /// Hexadecimal fr24_id of the flight
+        id: String,
+    },
+    /// Airport details by IATA or ICAO code
+    Airport {
+        code: String,
+        /// Use the light endpoint
+        #[arg(long)]
+        light: bool,
+    },
+    /// Airline details by ICAO code
+    Airline { icao: String },
+    /// API usage over a period (24h, 7d, 30d or 1y)
+    Usage { period: String },
+    /// Continuously refreshing dashboard of live traffic
+    #[cfg(feature = "tui")]
+    Watch {
+        /// Seconds between refreshes
+        #[arg(long, default_value_t = 10)]
+        interval: u64,
+        #[command(flatten)]
+        query: QueryArgs,
+    },
+}
+
+/// Filters of `FullLiveFlightQuery`, lists are comma separated
+#[derive(Args)]
+struct QueryArgs {
+    /// Bounding box as `north,south,west,east`
+    #[arg(long, value_parser = parse_bounds, allow_hyphen_values = true)]
+    bounds: Option<Bounds>,
+    #[arg(long, value_delimiter = ',')]
+    flights: Vec<String>,
+    #[arg(long, value_delimiter = ',')]
+    callsigns: Vec<String>,
+    #[arg(long, value_delimiter = ',')]
+    registrations: Vec<String>,
+    #[arg(long, value_delimiter = ',')]
+    painted_as: Vec<String>,
+    #[arg(long, value_delimiter = ',')]
+    operating_as: Vec<String>,
+    /// Airports with optional `inbound:`, `outbound:` or `both:` prefix
+    #[arg(long, value_delimiter = ',')]
+    airports: Vec<AirportFilter>,
+    /// Routes as `FROM-TO` between airports or countries
+    #[arg(long, value_delimiter = ',')]
+    routes: Vec<RouteFilter>,
+    /// Aircraft ICAO type designators, with one `*` wildcard or as `family:NAME`
+    #[arg(long, value_delimiter = ',')]
+    aircraft: Vec<AircraftTypeFilter>,
+    /// Altitude ranges as `min-max` in feet
+    #[arg(long, value_delimiter = ',', value_parser = parse_range)]
+    altitude_ranges: Vec<ApiRange>,
+    #[arg(long, value_delimiter = ',')]
+    squawks: Vec<u16>,
+    #[arg(long, value_delimiter = ',')]
+    categories: Vec<char>,
+    /// ADSB, MLAT or ESTIMATED
+    #[arg(long, value_delimiter = ',')]
+    data_sources: Vec<String>,
+    #[arg(long, value_delimiter = ',')]
+    airspaces: Vec<String>,
+    /// Ground speed as `value` or `min-max` in knots
+    #[arg(long, value_parser = parse_gspeed)]
+    gspeed: Option<ApiRangeEnum>,
+    #[arg(long)]
+    limit: Option<u32>,
+}
+
+impl QueryArgs {
+    fn into_query(self) -> FullLiveFlightQuery {
+        fn some<T>(values: Vec<T>) -> Option<Vec<T>> {
+            (!values.is_empty()).then_some(values)
+        }
+
+        FullLiveFlightQuery {
+            bounds: self.bounds,
+            flights: some(self.flights),
+            callsigns: some(self.callsigns),
+            registrations: some(self.registrations),
+            painted_as: some(self.painted_as),
+            operating_as: some(self.operating_as),
+            airports: some(self.airports),
+            routes: some(self.routes),
+            aircraft: some(self.aircraft),
+            altitude_ranges: some(self.altitude_ranges),
+            squawks: some(self.squawks),
+            categories: some(self.categories),
+            data_sources: some(self.data_sources),
+            airspaces: some(self.airspaces),
+            gspeed: self.gspeed,
+            limit: self.limit,
+        }
+    }
+}
+
+fn parse_bounds(value: &str) -> Result<Bounds, String> {
+    let parts: Vec<f64> = value
+        .split(',')
+        .map(|p| p.trim().parse::<f64>().map_err(|e| e.to_string()))
+        .collect::<Result<_, _>>()?;
+    match parts[..] {
+        [north, south, ..] if south > north => {
+            Err(format!("south {} is above north {}", south, north))
+        }
+        [north, south, west, east] => Ok(Bounds {
+            north,
+            south,
+            west,
+            east,
+        }),
+        _ => Err("expected north,south,west,east".to_string()),
+    }
+}
+
+fn parse_range(value: &str) -> Result<ApiRange, String> {
+    let (min, max) = value
+        .split_once('-')
+        .ok_or_else(|| "expected min-max".to_string())?;
+    let range = ApiRange {
+        min: min.trim().parse().map_err(|e| format!("{}", e))?,
+        max: max.trim().parse().map_err(|e| format!("{}", e))?,
+    };
+    if range.min > range.max {
+        return Err(format!("min {} is above max {}", range.min, range.max));
+    }
+    Ok(range)
+}
+
+fn parse_gspeed(value: &str) -> Result<ApiRangeEnum, String> {
+    if value.contains('-') {
+        parse_range(value).map(ApiRangeEnum::ApiRange)
+    } else {
+        value
+            .trim()
+            .parse()
+            .map(ApiRangeEnum::U32)
+            .map_err(|e| format!("{}", e))
+    }
+}
+
+/// Command result in every supported representation
+struct Output {
+    headers: Vec<&'static str>,
+    rows: Vec<Vec<String>>,
+    json: Value,
+    geojson: Option<Value>,
+}
+
+fn main() -> ExitCode {
+    dotenv().ok();
+    let cli = Cli::parse();
+
+    let mut client = FlightRadarClient::new(cli.api_key);
+    if cli.sandbox {
+        client.update_base_url(SANDBOX_URL.to_string());
+    } else if let Some(base_url) = cli.base_url {
+        client.update_base_url(base_url);
+    }
+
+    let output = match run(&client, cli.command) {
+        Ok(Some(output)) => output,
+        Ok(None) => return ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match cli.format {
+        OutputFormat::Table => print_table(&output),
+        OutputFormat::Csv => {
+            if let Err(e) = print_csv(&output) {
+                eprintln!("Writing CSV: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+        OutputFormat::Json => println!("{:#}", output.json),
+        OutputFormat::Geojson => match &output.geojson {
+            Some(geojson) => println!("{:#}", geojson),
+            None => {
+                eprintln!("GeoJSON output is not available for this command");
+                return ExitCode::FAILURE;
+            }
+        },
+    }
+
+    ExitCode::SUCCESS
+}
+
+fn run(client: &FlightRadarClient, command: Command) -> Result<Option<Output>, FlightRadarError> {
+    let output = match command {
+        Command::Live { light, query } => {
+            let query = query.into_query();
+            if light {
+                Ok(light_output(&client.get_live_flight_light(Some(&query))?))
+            } else {
+                Ok(full_output(&client.get_live_flight(Some(&query))?))
+            }
+        }
+        Command::Historic {
+            timestamp,
+            light,
+            query,
+        } => {
+            let query = query.into_query();
+            if light {
+                Ok(light_output(
+                    &client.get_historic_flight_light(&timestamp, Some(&query))?,
+                ))
+            } else {
+                Ok(full_output(
+                    &client.get_historic_flight(&timestamp, Some(&query))?,
+                ))
+            }
+        }
+        Command::Tracks { id } => Ok(tracks_output(&client.get_flight_tracks_by_id(&id)?)),
+        Command::Airport { code, light } => {
+            if light {
+                let airport = client.get_airport_lite_by_code(&code)?;
+                Ok(Output {
+                    headers: vec!["name", "iata", "icao"],
+                    rows: vec![vec![
+                        airport.name.to_string(),
+                        airport.iata.to_string(),
+                        airport.icao.to_string(),
+                    ]],
+                    json: to_json(&airport),
+                    geojson: None,
+                })
+            } else {
+                let airport = client.get_airport_by_code(&code)?;
+                Ok(Output {
+                    headers: vec![
+                        "name",
+                        "iata",
+                        "icao",
+                        "lat",
+                        "lon",
+                        "elevation",
+                        "city",
+                        "country",
+                        "timezone",
+                    ],
+                    rows: vec![vec![
+                        airport.name.to_string(),
+                        airport.iata.to_string(),
+                        airport.icao.to_string(),
+                        airport.lat.to_string(),
+                        airport.lon.to_string(),
+                        airport.elevation.to_string(),
+                        airport.city.to_string(),
+                        airport.country.name.to_string(),
+                        airport.timezone.name.to_string(),
+                    ]],
+                    json: to_json(&airport),
+                    geojson: Some(point_collection(vec![(
+                        airport.lon,
+                        airport.lat,
+                        to_json(&airport),
+                    )])),
+                })
+            }
+        }
+        Command::Airline { icao } => {
+            let airline = client.get_airline_by_icao(&icao)?;
+            Ok(Output {
+                headers: vec!["name", "iata", "icao"],
+                rows: vec![vec![
+                    airline.name.to_string(),
+                    airline.iata.clone().unwrap_or_default(),
+                    airline.icao.to_string(),
+                ]],
+                json: to_json(&airline),
+                geojson: None,
+            })
+        }
+        Command::Usage { period } => {
+            let usage = client.get_api_usage(&period)?;
+            Ok(Output {
+                headers: vec!["endpoint", "metadata", "requests", "results", "credits"],
+                rows: usage
+                    .data
+                    .iter()
+                    .map(|u| {
+                        vec![
+                            u.endpoint.to_string(),
+                            u.metadata.to_string(),
+                            u.request_count.to_string(),
+                            u.results.to_string(),
+                            u.credits.to_string(),
+                        ]
+                    })
+                    .collect(),
+                json: to_json(&usage),
+                geojson: None,
+            })
+        }
+        #[cfg(feature = "tui")]
+        Command::Watch { interval, query } => {
+            watch::run(client, query.into_query(), Duration::from_secs(interval))?;
+            return Ok(None);
+        }
+    };
+
+    output.map(Some)
+}
+
+fn full_output(response: &FullLiveFlightResponse) -> Output {
+    Output {
+        headers: vec![
+            "fr24_id",
+            "flight",
+            "callsign",
+            "type",
+            "reg",
+            "lat",
+            "lon",
+            "alt",
+            "gspeed",
+            "vspeed",
+            "track",
+            "squawk",
+            "orig",
+            "dest",
+            "eta",
+            "timestamp",
+        ],
+        rows: response
+            .data
+            .iter()
+            .map(|d| {
+                vec![
+                    d.fr24_id.to_string(),
+                    d.flight.to_string(),
+                    d.callsign.to_string(),
+                    d.type_field.to_string(),
+                    d.reg.to_string(),
+                    d.lat.to_string(),
+                    d.lon.to_string(),
+                    d.alt.to_string(),
+                    d.gspeed.to_string(),
+                    d.vspeed.to_string(),
+                    d.track.to_string(),
+                    d.squawk.to_string(),
+                    d.orig_iata.to_string(),
+                    d.dest_iata.to_string(),
+                    d.eta.to_string(),
+                    d.timestamp.to_string(),
+                ]
+            })
+            .collect(),
+        json: to_json(response),
+        geojson: Some(point_collection(
+            response
+                .data
+                .iter()
+                .map(|d| (d.lon, d.lat, to_json(d)))
+                .collect(),
+        )),
+    }
+}
+
+fn light_output(response: &LightLiveFlightResponse) -> Output {
+    Output {
+        headers: vec![
+            "fr24_id",
+            "hex",
+            "callsign",
+            "lat",
+            "lon",
+            "alt",
+            "gspeed",
+            "vspeed",
+            "track",
+            "squawk",
+            "source",
+            "timestamp",
+        ],
+        rows: response
+            .data
+            .iter()
+            .map(|d| {
+                vec![
+                    d.fr24_id.to_string(),
+                    d.hex.to_string(),
+                    d.callsign.to_string(),
+                    d.lat.to_string(),
+                    d.lon.to_string(),
+                    d.alt.to_string(),
+                    d.gspeed.to_string(),
+                    d.vspeed.to_string(),
+                    d.track.to_string(),
+                    d.squawk.to_string(),
+                    d.source.to_string(),
+                    d.timestamp.to_string(),
+                ]
+            })
+            .collect(),
+        json: to_json(response),
+        geojson: Some(point_collection(
+            response
+                .data
+                .iter()
+                .map(|d| (d.lon, d.lat, to_json(d)))
+                .collect(),
+        )),
+    }
+}
+
+fn tracks_output(flights: &[Flight]) -> Output {
+    Output {
+        headers: vec![
+            "fr24_id",
+            "timestamp",
+            "lat",
+            "lon",
+            "alt",
+            "gspeed",
+            "vspeed",
+            "track",
+            "squawk",
+            "callsign",
+            "source",
+        ],
+        rows: flights
+            .iter()
+            .flat_map(|f| {
+                f.tracks.iter().map(|t| {
+                    vec![
+                        f.id.to_string(),
+                        t.timestamp.to_string(),
+                        t.lat.to_string(),
+                        t.lon.to_string(),
+                        t.alt.to_string(),
+                        t.gspeed.to_string(),
+                        t.vspeed.to_string(),
+                        t.track.to_string(),
+                        t.squawk.to_string(),
+                        t.callsign.to_string(),
+                        t.source.to_string(),
+                    ]
+                })
+            })
+            .collect(),
+        json: to_json(&flights),
+        geojson: Some(flights_to_geojson(flights)),
+    }
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
+
+fn point_collection(points: Vec<(f64, f64, Value)>) -> Value {
+    let features: Vec<Value> = points
+        .into_iter()
+        .map(|(lon, lat, properties)| {
+            json!({
+                "type": "Feature",
+                "geometry": { "type": "Point", "coordinates": [lon, lat] },
+                "properties": properties,
+            })
+        })
+        .collect();
+    json!({ "type": "FeatureCollection", "features": features })
+}
+
+fn print_table(output: &Output) {
+    let mut widths: Vec<usize> = output.headers.iter().map(|h| h.len()).collect();
+    for row in output.rows.iter() {
+        for (width, cell) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let line = |cells: Vec<&str>| {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(widths.iter())
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        println!("{}", padded.join("  ").trim_end());
+    };
+
+    line(output.headers.clone());
+    let separator: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+    line(separator.iter().map(|s| s.as_str()).collect());
+    for row in output.rows.iter() {
+        line(row.iter().map(|c| c.as_str()).collect());
+    }
+}
+
+fn print_csv(output: &Output) -> Result<(), csv::Error> {
+    let mut writer = csv::Writer::from_writer(std::io::stdout().lock());
+    writer.write_record(&output.headers)?;
+    for row in output.rows.iter() {
+        writer.write_record(row)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_parse_bounds() {
+        let bounds = parse_bounds("42.473, 37.331,-10.014,-4.115").unwrap();
+        assert_eq!(
+            (42.473, 37.331, -10.014, -4.115),
+            (bounds.north, bounds.south, bounds.west, bounds.east)
+        );
+        // West above east crosses the antimeridian
+        assert!(parse_bounds("10,-10,170,-170").is_ok());
+
+        for bad in [
+            "42.473,37.331,-10.014",
+            "42,37,-10,-4,1",
+            "north,37,-10,-4",
+            "",
+            "37,42,-10,-4",
+        ] {
+            assert!(parse_bounds(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn check_parse_range() {
+        let range = parse_range("1000 - 35000").unwrap();
+        assert_eq!((1000, 35000), (range.min, range.max));
+        assert!(parse_range("5000-5000").is_ok());
+
+        for bad in [
+            "1000",
+            "-35000",
+            "1000-",
+            "low-high",
+            "35000-1000",
+            "1000-2000-3000",
+        ] {
+            assert!(parse_range(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn check_parse_gspeed() {
+        assert!(matches!(parse_gspeed("250"), Ok(ApiRangeEnum::U32(250))));
+        assert!(matches!(
+            parse_gspeed("100-250"),
+            Ok(ApiRangeEnum::ApiRange(ApiRange { min: 100, max: 250 }))
+        ));
+
+        for bad in ["fast", "", "-5", "250-100", "100-fast"] {
+            assert!(parse_gspeed(bad).is_err(), "{}", bad);
+        }
+    }
+}
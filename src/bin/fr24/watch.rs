@@ -0,0 +1,418 @@
+/// Interactive terminal dashboard for live traffic
+use std::time::{Duration, Instant};
+
+use flightradar24_api::client::*;
+use flightradar24_api::error::FlightRadarError;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::symbols::Marker;
+use ratatui::text::Line;
+use ratatui::widgets::canvas::{self, Canvas, Map, MapResolution, Points};
+use ratatui::widgets::{Block, Borders, Paragraph, Row, Table, TableState};
+use ratatui::{DefaultTerminal, Frame};
+
+/// Hijack, radio failure and general emergency
+const EMERGENCY_SQUAWKS: [&str; 3] = ["7500", "7600", "7700"];
+
+#[derive(Clone, Copy, PartialEq)]
+enum SortKey {
+    Callsign,
+    Altitude,
+    Speed,
+    Squawk,
+    Type,
+}
+
+impl SortKey {
+    fn next(self) -> Self {
+        match self {
+            SortKey::Callsign => SortKey::Altitude,
+            SortKey::Altitude => SortKey::Speed,
+            SortKey::Speed => SortKey::Squawk,
+            SortKey::Squawk => SortKey::Type,
+            SortKey::Type => SortKey::Callsign,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            SortKey::Callsign => "callsign",
+            SortKey::Altitude => "altitude",
+            SortKey::Speed => "speed",
+            SortKey::Squawk => "squawk",
+            SortKey::Type => "type",
+        }
+    }
+}
+
+struct App<'a> {
+    client: &'a FlightRadarClient,
+    query: FullLiveFlightQuery,
+    interval: Duration,
+    flights: Vec<FullLiveFlightData>,
+    table: TableState,
+    sort: SortKey,
+    descending: bool,
+    /// Tracks of the flight selected with Enter, keyed by fr24_id
+    track: Option<(String, Vec<Flight>)>,
+    status: String,
+    last_refresh: Option<Instant>,
+}
+
+/// Runs the dashboard until the user quits.
+/// # Arguments
+///   * `client` - Client used for live positions and tracks
+///   * `query` - Filters for the live positions
+///   * `interval` - Time between refreshes
+/// # Returns
+///   `()` on exit or a `FlightRadarError` if the terminal fails.
+pub fn run(
+    client: &FlightRadarClient,
+    query: FullLiveFlightQuery,
+    interval: Duration,
+) -> Result<(), FlightRadarError> {
+    let mut app = App {
+        client,
+        query,
+        interval,
+        flights: Vec::new(),
+        table: TableState::default(),
+        sort: SortKey::Callsign,
+        descending: false,
+        track: None,
+        status: String::new(),
+        last_refresh: None,
+    };
+
+    let mut terminal = ratatui::init();
+    let result = app.run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+fn is_emergency(flight: &FullLiveFlightData) -> bool {
+    EMERGENCY_SQUAWKS.contains(&flight.squawk.as_str())
+}
+
+impl App<'_> {
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<(), FlightRadarError> {
+        loop {
+            if self
+                .last_refresh
+                .is_none_or(|t| t.elapsed() >= self.interval)
+            {
+                self.refresh();
+            }
+            terminal.draw(|frame| self.draw(frame))?;
+
+            if !event::poll(Duration::from_millis(250))? {
+                continue;
+            }
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                    KeyCode::Down | KeyCode::Char('j') => self.select(1),
+                    KeyCode::Up | KeyCode::Char('k') => self.select(-1),
+                    KeyCode::Char('s') => {
+                        self.sort = self.sort.next();
+                        self.sort_flights();
+                    }
+                    KeyCode::Char('r') => {
+                        self.descending = !self.descending;
+                        self.sort_flights();
+                    }
+                    KeyCode::Char('R') | KeyCode::F(5) => self.last_refresh = None,
+                    KeyCode::Enter => self.load_track(),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    fn refresh(&mut self) {
+        match self.client.get_live_flight(Some(&self.query)) {
+            Ok(response) => {
+                self.flights = response.data;
+                self.sort_flights();
+                self.status.clear();
+            }
+            Err(e) => self.status = e.to_string(),
+        }
+        self.last_refresh = Some(Instant::now());
+    }
+
+    /// Sorts the flights, keeping the selection on the same fr24_id
+    fn sort_flights(&mut self) {
+        let selected = self.selected().map(|f| f.fr24_id.to_string());
+
+        match self.sort {
+            SortKey::Callsign => self.flights.sort_by(|a, b| a.callsign.cmp(&b.callsign)),
+            SortKey::Altitude => self.flights.sort_by_key(|f| f.alt),
+            SortKey::Speed => self.flights.sort_by_key(|f| f.gspeed),
+            // Emergencies first
+            SortKey::Squawk => self
+                .flights
+                .sort_by_key(|f| (!is_emergency(f), f.squawk.to_string())),
+            SortKey::Type => self.flights.sort_by(|a, b| a.type_field.cmp(&b.type_field)),
+        }
+        if self.descending {
+            self.flights.reverse();
+        }
+
+        let index = selected
+            .and_then(|id| self.flights.iter().position(|f| f.fr24_id == id))
+            .or((!self.flights.is_empty()).then_some(0));
+        self.table.select(index);
+    }
+
+    fn select(&mut self, offset: isize) {
+        if self.flights.is_empty() {
+            return;
+        }
+        let current = self.table.selected().unwrap_or(0) as isize;
+        let next = (current + offset).clamp(0, self.flights.len() as isize - 1);
+        self.table.select(Some(next as usize));
+    }
+
+    fn selected(&self) -> Option<&FullLiveFlightData> {
+        self.table.selected().and_then(|i| self.flights.get(i))
+    }
+
+    fn load_track(&mut self) {
+        let Some(id) = self.selected().map(|f| f.fr24_id.to_string()) else {
+            return;
+        };
+        match self.client.get_flight_tracks_by_id(&id) {
+            Ok(flights) => {
+                self.track = Some((id, flights));
+                self.status.clear();
+            }
+            Err(e) => self.status = e.to_string(),
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(10),
+                Constraint::Length(9),
+                Constraint::Length(1),
+            ])
+            .split(frame.area());
+        let top = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(rows[0]);
+
+        self.draw_table(frame, top[0]);
+        self.draw_map(frame, top[1]);
+        self.draw_details(frame, rows[1]);
+
+        let age = self
+            .last_refresh
+            .map(|t| t.elapsed().as_secs())
+            .unwrap_or_default();
+        let status = format!(
+            " q quit  ↑/↓ select  s sort ({}{})  r reverse  Enter track  R refresh | {} aircraft | updated {}s ago {}",
+            self.sort.label(),
+            if self.descending { " desc" } else { "" },
+            self.flights.len(),
+            age,
+            self.status
+        );
+        frame.render_widget(
+            Paragraph::new(status).style(Style::default().add_modifier(Modifier::REVERSED)),
+            rows[2],
+        );
+    }
+
+    fn draw_table(&mut self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let header = Row::new([
+            "Callsign", "Flight", "Type", "Reg", "Alt", "GS", "VS", "Trk", "Sqk", "Route",
+        ])
+        .style(Style::default().add_modifier(Modifier::BOLD));
+
+        let rows = self.flights.iter().map(|f| {
+            let style = if is_emergency(f) {
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            Row::new([
+                f.callsign.to_string(),
+                f.flight.to_string(),
+                f.type_field.to_string(),
+                f.reg.to_string(),
+                f.alt.to_string(),
+                f.gspeed.to_string(),
+                f.vspeed.to_string(),
+                f.track.to_string(),
+                f.squawk.to_string(),
+                format!("{}-{}", f.orig_iata, f.dest_iata),
+            ])
+            .style(style)
+        });
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(8),
+                Constraint::Length(8),
+                Constraint::Length(5),
+                Constraint::Length(8),
+                Constraint::Length(6),
+                Constraint::Length(4),
+                Constraint::Length(6),
+                Constraint::Length(4),
+                Constraint::Length(5),
+                Constraint::Min(7),
+            ],
+        )
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title(" Traffic "))
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+        frame.render_stateful_widget(table, area, &mut self.table);
+    }
+
+    fn draw_map(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let (south, north, west, east) = self.map_extent();
+        let selected_id = self.selected().map(|f| f.fr24_id.as_str());
+        let track = self
+            .track
+            .as_ref()
+            .filter(|(id, _)| Some(id.as_str()) == selected_id)
+            .map(|(_, flights)| flights);
+
+        let normal: Vec<(f64, f64)> = self
+            .flights
+            .iter()
+            .filter(|f| !is_emergency(f))
+            .map(|f| (f.lon, f.lat))
+            .collect();
+        let emergency: Vec<(f64, f64)> = self
+            .flights
+            .iter()
+            .filter(|f| is_emergency(f))
+            .map(|f| (f.lon, f.lat))
+            .collect();
+        let selected: Vec<(f64, f64)> = self
+            .selected()
+            .map(|f| (f.lon, f.lat))
+            .into_iter()
+            .collect();
+
+        let map = Canvas::default()
+            .block(Block::default().borders(Borders::ALL).title(" Map "))
+            .marker(Marker::Braille)
+            .x_bounds([west, east])
+            .y_bounds([south, north])
+            .paint(move |ctx| {
+                ctx.draw(&Map {
+                    resolution: MapResolution::High,
+                    color: Color::DarkGray,
+                });
+                ctx.layer();
+                if let Some(flights) = track {
+                    for flight in flights.iter() {
+                        for pair in flight.tracks.windows(2) {
+                            ctx.draw(&canvas::Line {
+                                x1: pair[0].lon,
+                                y1: pair[0].lat,
+                                x2: pair[1].lon,
+                                y2: pair[1].lat,
+                                color: Color::Yellow,
+                            });
+                        }
+                    }
+                }
+                ctx.draw(&Points {
+                    coords: &normal,
+                    color: Color::Cyan,
+                });
+                ctx.draw(&Points {
+                    coords: &emergency,
+                    color: Color::Red,
+                });
+                ctx.draw(&Points {
+                    coords: &selected,
+                    color: Color::Yellow,
+                });
+            });
+
+        frame.render_widget(map, area);
+    }
+
+    /// Map extent as `(south, north, west, east)`, from the query bounds or the traffic
+    fn map_extent(&self) -> (f64, f64, f64, f64) {
+        if let Some(bounds) = &self.query.bounds {
+            return (bounds.south, bounds.north, bounds.west, bounds.east);
+        }
+        if self.flights.is_empty() {
+            return (-90.0, 90.0, -180.0, 180.0);
+        }
+
+        let fold = |init: f64, pick: fn(f64, f64) -> f64, value: fn(&FullLiveFlightData) -> f64| {
+            self.flights.iter().map(value).fold(init, pick)
+        };
+        (
+            fold(90.0, f64::min, |f| f.lat) - 0.5,
+            fold(-90.0, f64::max, |f| f.lat) + 0.5,
+            fold(180.0, f64::min, |f| f.lon) - 0.5,
+            fold(-180.0, f64::max, |f| f.lon) + 0.5,
+        )
+    }
+
+    fn draw_details(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
+        let block = Block::default().borders(Borders::ALL).title(" Details ");
+        let Some(f) = self.selected() else {
+            frame.render_widget(Paragraph::new("No aircraft").block(block), area);
+            return;
+        };
+
+        let track_line = match &self.track {
+            Some((id, flights)) if *id == f.fr24_id => {
+                let points: usize = flights.iter().map(|fl| fl.tracks.len()).sum();
+                format!("Track: {} points", points)
+            }
+            _ => "Track: press Enter to load".to_string(),
+        };
+        let squawk = if is_emergency(f) {
+            Line::styled(
+                format!("Squawk: {} EMERGENCY", f.squawk),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            )
+        } else {
+            Line::from(format!("Squawk: {}", f.squawk))
+        };
+
+        let lines = vec![
+            Line::from(format!(
+                "{} / {}  ({})  hex {}",
+                f.callsign, f.flight, f.fr24_id, f.hex
+            )),
+            Line::from(format!(
+                "Aircraft: {} {}  Operator: {}  Livery: {}",
+                f.type_field, f.reg, f.operating_as, f.painted_as
+            )),
+            Line::from(format!(
+                "Route: {} ({}) -> {} ({})  ETA: {}",
+                f.orig_iata, f.orig_icao, f.dest_iata, f.dest_icao, f.eta
+            )),
+            Line::from(format!(
+                "Position: {:.4}, {:.4}  Alt: {} ft  GS: {} kt  VS: {} fpm  Track: {}°",
+                f.lat, f.lon, f.alt, f.gspeed, f.vspeed, f.track
+            )),
+            squawk,
+            Line::from(format!("Source: {}  Seen: {}", f.source, f.timestamp)),
+            Line::from(track_line),
+        ];
+
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+}
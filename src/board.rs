@@ -0,0 +1,186 @@
+/// Airport arrivals and departures boards built from live flight data
+use crate::client::{Airport, FlightRadarClient, FullLiveFlightData, FullLiveFlightQuery};
+use crate::error::FlightRadarError;
+use crate::filter::AirportFilter;
+use crate::geo;
+use chrono::{DateTime, FixedOffset};
+
+/// Distance (nautical miles) from the airport within which a grounded aircraft is at it
+pub const AIRPORT_RADIUS_NM: f64 = 5.0;
+
+/// Progress of a flight as shown on the board
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FlightStatus {
+    /// On the ground at the board's airport, not yet departed
+    Taxiing,
+    /// Left the board's airport, airborne or on the ground elsewhere
+    Departed,
+    /// Towards the board's airport, airborne or not yet left the origin
+    EnRoute,
+    /// On the ground at the board's airport
+    Landed,
+}
+
+impl std::fmt::Display for FlightStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FlightStatus::Taxiing => write!(f, "Taxiing"),
+            FlightStatus::Departed => write!(f, "Departed"),
+            FlightStatus::EnRoute => write!(f, "En Route"),
+            FlightStatus::Landed => write!(f, "Landed"),
+        }
+    }
+}
+
+/// Single line of an arrivals or departures board
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoardEntry {
+    pub fr24_id: String,
+    pub flight: String,
+    pub callsign: String,
+    pub type_field: String,
+    pub reg: String,
+    pub operating_as: String,
+    /// Origin for arrivals, destination for departures (IATA when known, else ICAO)
+    pub other_airport: String,
+    /// Estimated arrival in the airport's local time
+    pub eta: Option<DateTime<FixedOffset>>,
+    /// Time of the position report in the airport's local time
+    pub last_seen: Option<DateTime<FixedOffset>>,
+    pub status: FlightStatus,
+    /// Distance from the board's airport in nautical miles
+    pub distance_nm: f64,
+}
+
+/// Arrivals and departures for one airport
+#[derive(Debug)]
+pub struct AirportBoard {
+    pub airport: Airport,
+    /// Ordered by ETA, landed flights first
+    pub arrivals: Vec<BoardEntry>,
+    /// Ordered by distance from the airport, aircraft still on the ground first
+    pub departures: Vec<BoardEntry>,
+}
+
+impl AirportBoard {
+    /// Builds a board from live flight data.
+    ///
+    /// Times use `Airport.timezone.offset`, the airport's current UTC offset.
+    /// # Arguments
+    ///   * `airport` - Airport the board is for
+    ///   * `flights` - Aircraft states from the `flight-positions` endpoint
+    /// # Returns
+    ///   The `AirportBoard`, flights to or from other airports are left out
+    pub fn from_flights(airport: Airport, flights: &[FullLiveFlightData]) -> Self {
+        let offset = FixedOffset::east_opt(airport.timezone.offset)
+            .unwrap_or_else(|| FixedOffset::east_opt(0).expect("zero offset"));
+
+        let mut arrivals = Vec::new();
+        let mut departures = Vec::new();
+        for flight in flights {
+            let distance_nm = geo::haversine_nm(airport.lat, airport.lon, flight.lat, flight.lon);
+            let at_airport =
+                geo::on_ground(flight.alt, flight.gspeed) && distance_nm <= AIRPORT_RADIUS_NM;
+
+            if matches_airport(&airport, &flight.dest_iata, &flight.dest_icao) {
+                let status = if at_airport {
+                    FlightStatus::Landed
+                } else {
+                    FlightStatus::EnRoute
+                };
+                arrivals.push(entry(
+                    flight,
+                    &flight.orig_iata,
+                    &flight.orig_icao,
+                    status,
+                    distance_nm,
+                    &offset,
+                ));
+            } else if matches_airport(&airport, &flight.orig_iata, &flight.orig_icao) {
+                let status = if at_airport {
+                    FlightStatus::Taxiing
+                } else {
+                    FlightStatus::Departed
+                };
+                departures.push(entry(
+                    flight,
+                    &flight.dest_iata,
+                    &flight.dest_icao,
+                    status,
+                    distance_nm,
+                    &offset,
+                ));
+            }
+        }
+
+        arrivals.sort_by_key(|e| (e.status != FlightStatus::Landed, e.eta.is_none(), e.eta));
+        departures.sort_by(|a, b| {
+            a.status
+                .cmp(&b.status)
+                .then_with(|| a.distance_nm.total_cmp(&b.distance_nm))
+        });
+
+        AirportBoard {
+            airport,
+            arrivals,
+            departures,
+        }
+    }
+}
+
+impl FlightRadarClient {
+    /// Fetches the arrivals and departures board of an airport.
+    /// # Arguments
+    ///   * `code` - IATA or ICAO code of the airport
+    /// # Returns
+    ///   An `AirportBoard` on success or a `FlightRadarError` on failure.
+    pub fn get_airport_board(&self, code: &str) -> Result<AirportBoard, FlightRadarError> {
+        let filter = AirportFilter::both(code)?;
+        let airport = self.get_airport_by_code(code)?;
+        let query = FullLiveFlightQuery {
+            airports: Some(vec![filter]),
+            ..Default::default()
+        };
+        let live = self.get_live_flight(Some(&query))?;
+
+        Ok(AirportBoard::from_flights(airport, &live.data))
+    }
+}
+
+fn matches_airport(airport: &Airport, iata: &str, icao: &str) -> bool {
+    (!iata.is_empty() && iata.eq_ignore_ascii_case(&airport.iata))
+        || (!icao.is_empty() && icao.eq_ignore_ascii_case(&airport.icao))
+}
+
+fn entry(
+    flight: &FullLiveFlightData,
+    other_iata: &str,
+    other_icao: &str,
+    status: FlightStatus,
+    distance_nm: f64,
+    offset: &FixedOffset,
+) -> BoardEntry {
+    let local = |text: &str| {
+        DateTime::parse_from_rfc3339(text)
+            .ok()
+            .map(|t| t.with_timezone(offset))
+    };
+
+    BoardEntry {
+        fr24_id: flight.fr24_id.to_string(),
+        flight: flight.flight.to_string(),
+        callsign: flight.callsign.to_string(),
+        type_field: flight.type_field.to_string(),
+        reg: flight.reg.to_string(),
+        operating_as: flight.operating_as.to_string(),
+        other_airport: if other_iata.is_empty() {
+            other_icao.to_string()
+        } else {
+            other_iata.to_string()
+        },
+        eta: local(&flight.eta),
+        last_seen: local(&flight.timestamp),
+        status,
+        distance_nm,
+    }
+}
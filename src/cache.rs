@@ -0,0 +1,340 @@
+/// Response caching with per endpoint time-to-live policies
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Storage backend for raw API responses keyed by request URL
+pub trait ResponseCache: Send + Sync {
+    /// Returns the cached response if present and not expired.
+    fn get(&self, key: &str) -> Option<String>;
+    /// Stores a response, `ttl` of `None` never expires.
+    fn put(&self, key: &str, value: &str, ttl: Option<Duration>);
+}
+
+/// API endpoint groups sharing a cache lifetime
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum EndpointKind {
+    /// `static/airports` and `static/airlines`
+    Static,
+    /// `historic/flight-positions`
+    Historic,
+    /// `live/flight-positions`
+    Live,
+    /// `flight-tracks`
+    FlightTracks,
+    /// `usage`
+    Usage,
+}
+
+impl EndpointKind {
+    /// Classifies a request URL.
+    /// # Arguments
+    ///   * `url` - Full request URL
+    ///   * `base_url` - Base URL of the client issuing the request
+    /// # Returns
+    ///   `Some(EndpointKind)` for known endpoints, `None` otherwise
+    pub fn from_url(url: &str, base_url: &str) -> Option<Self> {
+        let path = url.strip_prefix(base_url).unwrap_or(url);
+        if path.starts_with("static/") {
+            Some(EndpointKind::Static)
+        } else if path.starts_with("historic/") {
+            Some(EndpointKind::Historic)
+        } else if path.starts_with("live/") {
+            Some(EndpointKind::Live)
+        } else if path.starts_with("flight-tracks") {
+            Some(EndpointKind::FlightTracks)
+        } else if path.starts_with("usage") {
+            Some(EndpointKind::Usage)
+        } else {
+            None
+        }
+    }
+}
+
+/// Lifetime of a cached response
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheTtl {
+    /// Never cache
+    Disabled,
+    /// Cache for the given duration
+    Expires(Duration),
+    /// Cache without expiry
+    Forever,
+}
+
+/// Cache lifetimes per endpoint group
+#[derive(Debug, Clone)]
+pub struct CachePolicy {
+    pub static_data: CacheTtl,
+    pub historic: CacheTtl,
+    pub live: CacheTtl,
+    pub flight_tracks: CacheTtl,
+    pub usage: CacheTtl,
+}
+
+impl Default for CachePolicy {
+    fn default() -> Self {
+        CachePolicy {
+            static_data: CacheTtl::Expires(Duration::from_secs(7 * 24 * 60 * 60)),
+            historic: CacheTtl::Forever,
+            live: CacheTtl::Expires(Duration::from_secs(5)),
+            flight_tracks: CacheTtl::Expires(Duration::from_secs(60)),
+            usage: CacheTtl::Disabled,
+        }
+    }
+}
+
+impl CachePolicy {
+    /// Lifetime configured for an endpoint group
+    pub fn ttl(&self, kind: EndpointKind) -> CacheTtl {
+        match kind {
+            EndpointKind::Static => self.static_data,
+            EndpointKind::Historic => self.historic,
+            EndpointKind::Live => self.live,
+            EndpointKind::FlightTracks => self.flight_tracks,
+            EndpointKind::Usage => self.usage,
+        }
+    }
+}
+
+/// Hit and miss counters for one endpoint group
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EndpointCacheStats {
+    /// Requests answered from the cache (and therefore not billed)
+    pub hits: u64,
+    /// Requests sent to the API
+    pub misses: u64,
+}
+
+/// Cache usage per endpoint group
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub endpoints: BTreeMap<EndpointKind, EndpointCacheStats>,
+}
+
+impl CacheStats {
+    /// Total requests answered from the cache
+    pub fn hits(&self) -> u64 {
+        self.endpoints.values().map(|s| s.hits).sum()
+    }
+
+    /// Total requests sent to the API
+    pub fn misses(&self) -> u64 {
+        self.endpoints.values().map(|s| s.misses).sum()
+    }
+}
+
+/// Cache backend, policy and statistics attached to a `FlightRadarClient`
+pub(crate) struct CacheLayer {
+    cache: Box<dyn ResponseCache>,
+    policy: CachePolicy,
+    stats: Mutex<CacheStats>,
+}
+
+impl CacheLayer {
+    pub(crate) fn new(cache: Box<dyn ResponseCache>, policy: CachePolicy) -> Self {
+        CacheLayer {
+            cache,
+            policy,
+            stats: Mutex::new(CacheStats::default()),
+        }
+    }
+
+    /// Cached response for a request, recording the hit or miss
+    pub(crate) fn lookup(&self, url: &str, kind: Option<EndpointKind>) -> Option<String> {
+        let kind = kind?;
+        if self.policy.ttl(kind) == CacheTtl::Disabled {
+            return None;
+        }
+
+        let cached = self.cache.get(url);
+        let mut stats = self.stats.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = stats.endpoints.entry(kind).or_default();
+        match cached {
+            Some(_) => entry.hits += 1,
+            None => entry.misses += 1,
+        }
+        cached
+    }
+
+    /// Stores a successful response according to the policy
+    pub(crate) fn store(&self, url: &str, kind: Option<EndpointKind>, body: &str) {
+        match kind.map(|k| self.policy.ttl(k)) {
+            Some(CacheTtl::Expires(ttl)) => self.cache.put(url, body, Some(ttl)),
+            Some(CacheTtl::Forever) => self.cache.put(url, body, None),
+            _ => {}
+        }
+    }
+
+    pub(crate) fn stats(&self) -> CacheStats {
+        self.stats.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+/// In-memory least recently used cache
+pub struct MemoryCache {
+    capacity: usize,
+    state: Mutex<LruState>,
+}
+
+#[derive(Default)]
+struct LruState {
+    tick: u64,
+    entries: HashMap<String, MemoryEntry>,
+    order: BTreeMap<u64, String>,
+}
+
+struct MemoryEntry {
+    value: String,
+    expires: Option<Instant>,
+    tick: u64,
+}
+
+impl MemoryCache {
+    /// Creates a new in-memory cache.
+    /// # Arguments
+    ///   * `capacity` - Maximum number of responses kept before evicting the least recently used
+    pub fn new(capacity: usize) -> Self {
+        MemoryCache {
+            capacity: capacity.max(1),
+            state: Mutex::new(LruState::default()),
+        }
+    }
+
+    /// Number of responses currently held
+    pub fn len(&self) -> usize {
+        self.state
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entries
+            .len()
+    }
+
+    /// Returns `true` when no responses are held
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl ResponseCache for MemoryCache {
+    fn get(&self, key: &str) -> Option<String> {
+        let mut guard = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let state = &mut *guard;
+        state.tick += 1;
+        let tick = state.tick;
+
+        let entry = state.entries.get_mut(key)?;
+        if entry.expires.is_some_and(|e| e <= Instant::now()) {
+            let old_tick = entry.tick;
+            state.entries.remove(key);
+            state.order.remove(&old_tick);
+            return None;
+        }
+
+        let old_tick = std::mem::replace(&mut entry.tick, tick);
+        let value = entry.value.clone();
+        state.order.remove(&old_tick);
+        state.order.insert(tick, key.to_string());
+        Some(value)
+    }
+
+    fn put(&self, key: &str, value: &str, ttl: Option<Duration>) {
+        let mut guard = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let state = &mut *guard;
+        state.tick += 1;
+        let tick = state.tick;
+
+        let entry = MemoryEntry {
+            value: value.to_string(),
+            expires: ttl.map(|ttl| Instant::now() + ttl),
+            tick,
+        };
+        if let Some(old) = state.entries.insert(key.to_string(), entry) {
+            state.order.remove(&old.tick);
+        }
+        state.order.insert(tick, key.to_string());
+
+        while state.entries.len() > self.capacity {
+            match state.order.pop_first() {
+                Some((_, oldest)) => {
+                    state.entries.remove(&oldest);
+                }
+                None => break,
+            }
+        }
+    }
+}
+
+/// On-disk cache storing one JSON file per response
+pub struct DiskCache {
+    dir: PathBuf,
+}
+
+#[derive(Serialize, Deserialize)]
+struct DiskEntry {
+    key: String,
+    /// Unix seconds after which the entry is stale
+    expires_at: Option<u64>,
+    body: String,
+}
+
+impl DiskCache {
+    /// Creates a new on-disk cache, creating the directory if needed.
+    /// # Arguments
+    ///   * `dir` - Directory that holds the cache files
+    /// # Returns
+    ///   A `DiskCache` on success or a `std::io::Error` on failure.
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Result<Self, std::io::Error> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(DiskCache { dir })
+    }
+
+    fn path_for(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{:016x}.json", fnv1a(key)))
+    }
+}
+
+impl ResponseCache for DiskCache {
+    fn get(&self, key: &str) -> Option<String> {
+        let path = self.path_for(key);
+        let entry: DiskEntry = serde_json::from_str(&fs::read_to_string(&path).ok()?).ok()?;
+        if entry.key != key {
+            return None;
+        }
+        if entry.expires_at.is_some_and(|e| e <= unix_now()) {
+            let _ = fs::remove_file(path);
+            return None;
+        }
+        Some(entry.body)
+    }
+
+    fn put(&self, key: &str, value: &str, ttl: Option<Duration>) {
+        let entry = DiskEntry {
+            key: key.to_string(),
+            expires_at: ttl.map(|ttl| unix_now() + ttl.as_secs()),
+            body: value.to_string(),
+        };
+        // A failed write only costs a future cache miss
+        if let Ok(text) = serde_json::to_string(&entry) {
+            let _ = fs::write(self.path_for(key), text);
+        }
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Stable 64-bit FNV-1a hash used for cache file names
+fn fnv1a(value: &str) -> u64 {
+    value.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
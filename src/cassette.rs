@@ -0,0 +1,149 @@
+/// Record and replay of API interactions for deterministic tests
+use crate::error::FlightRadarError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Placeholder written in place of the API key
+pub const SCRUBBED_API_KEY: &str = "<API_KEY>";
+
+/// Single recorded request and response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub url: String,
+    pub status: u16,
+    pub body: String,
+}
+
+/// Ordered collection of recorded interactions
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+impl Cassette {
+    /// Reads a cassette file.
+    /// # Arguments
+    ///   * `path` - Location of the cassette JSON file
+    /// # Returns
+    ///   A `Cassette` on success or a `FlightRadarError` on failure.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, FlightRadarError> {
+        let text = fs::read_to_string(path)?;
+        serde_json::from_str(&text)
+            .map_err(|e| FlightRadarError::Parsing(format!("Cassette: {}", e)))
+    }
+
+    /// Writes the cassette as pretty printed JSON.
+    /// # Arguments
+    ///   * `path` - Location of the cassette JSON file
+    /// # Returns
+    ///   `()` on success or a `FlightRadarError` on failure.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), FlightRadarError> {
+        let text = serde_json::to_string_pretty(self)
+            .map_err(|e| FlightRadarError::Parsing(format!("Cassette: {}", e)))?;
+        fs::write(path, text)?;
+        Ok(())
+    }
+}
+
+/// Whether a client writes to or serves from its cassette
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    /// Send requests to the API and append every interaction to the cassette
+    Record,
+    /// Serve responses from the cassette without any network access
+    Replay,
+}
+
+/// Cassette state attached to a `FlightRadarClient`
+pub(crate) struct CassetteLayer {
+    mode: CassetteMode,
+    path: PathBuf,
+    state: Mutex<CassetteState>,
+}
+
+struct CassetteState {
+    cassette: Cassette,
+    /// Next interaction to replay per URL
+    cursors: HashMap<String, usize>,
+}
+
+impl CassetteLayer {
+    pub(crate) fn record(path: PathBuf) -> Self {
+        Self::with_cassette(CassetteMode::Record, path, Cassette::default())
+    }
+
+    pub(crate) fn replay(path: PathBuf) -> Result<Self, FlightRadarError> {
+        let cassette = Cassette::load(&path)?;
+        Ok(Self::with_cassette(CassetteMode::Replay, path, cassette))
+    }
+
+    fn with_cassette(mode: CassetteMode, path: PathBuf, cassette: Cassette) -> Self {
+        CassetteLayer {
+            mode,
+            path,
+            state: Mutex::new(CassetteState {
+                cassette,
+                cursors: HashMap::new(),
+            }),
+        }
+    }
+
+    pub(crate) fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+
+    /// Recorded response for a request.
+    ///
+    /// Repeated requests for the same URL are answered in recorded order, the
+    /// last matching interaction is reused once they run out.
+    pub(crate) fn replay_response(&self, url: &str) -> Result<(u16, String), FlightRadarError> {
+        let mut guard = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let state = &mut *guard;
+
+        let matches: Vec<&Interaction> = state
+            .cassette
+            .interactions
+            .iter()
+            .filter(|i| i.url == url)
+            .collect();
+        let cursor = state.cursors.entry(url.to_string()).or_default();
+        let interaction = matches.get(*cursor).or(matches.last()).ok_or_else(|| {
+            FlightRadarError::General(format!("No Recorded Interaction: {}", url))
+        })?;
+
+        *cursor += 1;
+        Ok((interaction.status, interaction.body.to_string()))
+    }
+
+    /// Appends an interaction and rewrites the cassette file.
+    pub(crate) fn record_interaction(
+        &self,
+        url: &str,
+        status: u16,
+        body: &str,
+    ) -> Result<(), FlightRadarError> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.cassette.interactions.push(Interaction {
+            url: url.to_string(),
+            status,
+            body: body.to_string(),
+        });
+        state.cassette.save(&self.path)
+    }
+}
+
+/// Replaces every occurrence of the API key with `SCRUBBED_API_KEY`.
+/// # Arguments
+///   * `text` - URL or response body to scrub
+///   * `api_key` - Key to remove
+/// # Returns
+///   The scrubbed `String`
+pub fn scrub_api_key(text: &str, api_key: &str) -> String {
+    if api_key.is_empty() {
+        return text.to_string();
+    }
+    text.replace(api_key, SCRUBBED_API_KEY)
+}
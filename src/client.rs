@@ -1,12 +1,41 @@
+use crate::cache::{CacheLayer, CachePolicy, CacheStats, EndpointKind, ResponseCache};
+use crate::cassette::{scrub_api_key, CassetteLayer, CassetteMode};
 use crate::error::FlightRadarError;
+use crate::filter::{AircraftTypeFilter, AirportFilter, RouteFilter};
+use crate::rate_limit::RateLimiter;
+use chrono::{DateTime, Utc};
 use reqwest::blocking::Client;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// Requests in flight at once for batch lookups such as `get_airports`
+pub const DEFAULT_BATCH_CONCURRENCY: usize = 4;
+
+/// Earliest timestamp accepted by the historic endpoints (2016-05-11 00:00:00 UTC)
+pub const MIN_TIMESTAMP: u64 = 1462924800;
+
+/// Where the response to a request came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseSource {
+    /// Fetched from the API, charged by FR24
+    Network,
+    /// Served by the response cache
+    Cache,
+    /// Served by a replayed cassette
+    Cassette,
+}
 
 /// Main structure for storing API internal data
 pub struct FlightRadarClient {
     client: Client,
     base_url: String,
     api_key: String,
+    cache: Option<CacheLayer>,
+    cassette: Option<CassetteLayer>,
+    rate_limiter: Option<RateLimiter>,
 }
 
 /// FlightRadarClient for interacting with API
@@ -20,6 +49,9 @@ impl FlightRadarClient {
             // This is an example base URL. Adjust as needed.
             base_url: "https://fr24api.flightradar24.com/api/".to_string(),
             api_key,
+            cache: None,
+            cassette: None,
+            rate_limiter: None,
         }
     }
 
@@ -27,12 +59,59 @@ impl FlightRadarClient {
         self.base_url = base_url;
     }
 
+    /// Base URL requests are sent to
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Limits requests sent to the API, blocking until the limiter allows them.
+    /// Cached and replayed responses don't count against the limit.
+    /// # Arguments
+    ///   * `limiter` - Limiter shared by every request of this client
+    pub fn set_rate_limit(&mut self, limiter: RateLimiter) {
+        self.rate_limiter = Some(limiter);
+    }
+
+    /// Caches API responses, replacing any previously configured cache.
+    /// # Arguments
+    ///   * `cache` - Storage backend such as `MemoryCache` or `DiskCache`
+    ///   * `policy` - Time-to-live of cached responses per endpoint group
+    pub fn set_cache(&mut self, cache: Box<dyn ResponseCache>, policy: CachePolicy) {
+        self.cache = Some(CacheLayer::new(cache, policy));
+    }
+
+    /// Cache hits and misses per endpoint group since `set_cache` was called.
+    /// # Returns
+    ///   `Some(CacheStats)` when a cache is configured, `None` otherwise
+    pub fn cache_stats(&self) -> Option<CacheStats> {
+        self.cache.as_ref().map(|cache| cache.stats())
+    }
+
+    /// Records every API interaction into a new cassette file, with the API key scrubbed.
+    /// # Arguments
+    ///   * `path` - Location of the cassette JSON file, rewritten after each request
+    pub fn record_cassette<P: Into<PathBuf>>(&mut self, path: P) {
+        self.cassette = Some(CassetteLayer::record(path.into()));
+    }
+
+    /// Serves every request from a recorded cassette without network access.
+    /// # Arguments
+    ///   * `path` - Location of the cassette JSON file
+    /// # Returns
+    ///   `()` on success or a `FlightRadarError` if the cassette can't be read.
+    pub fn replay_cassette<P: Into<PathBuf>>(&mut self, path: P) -> Result<(), FlightRadarError> {
+        self.cassette = Some(CassetteLayer::replay(path.into())?);
+        Ok(())
+    }
+
     /// Build parameters string for query URL
     /// # Arguments
     ///   * `params` - Structure of all possible parameters
     /// # Returns
     ///   `String` of params on success or a `FlightRadarError` on failure.
-    fn build_query_params(params: &FullLiveFlightQuery) -> Result<String, FlightRadarError> {
+    pub(crate) fn build_query_params(
+        params: &FullLiveFlightQuery,
+    ) -> Result<String, FlightRadarError> {
         let mut url: String = String::new();
 
         if let Some(bounds) = &params.bounds {
@@ -124,53 +203,18 @@ impl FlightRadarClient {
         }
         if let Some(airports) = &params.airports {
             url.push_str("&airports=");
-            for airport in airports {
-                if airport
-                    .chars()
-                    .all(|c| (c.is_alphabetic() && (c != 'i' || c != 'q' || c != 'x')) || c == ':')
-                {
-                    url.push_str(&airport.to_string());
-                    url.push(',');
-                } else {
-                    return Err(FlightRadarError::Parameter(format!("Airport: {}", airport)));
-                }
-            }
-            url.pop();
+            let airports: Vec<String> = airports.iter().map(|a| a.to_string()).collect();
+            url.push_str(&airports.join(","));
         }
         if let Some(routes) = &params.routes {
             url.push_str("&routes=");
-            for route in routes {
-                if route
-                    .chars()
-                    .all(|c| (c.is_alphabetic() && (c != 'i' || c != 'q' || c != 'x')) || c == '-')
-                {
-                    url.push_str(&route.to_string());
-                    url.push(',');
-                } else {
-                    return Err(FlightRadarError::Parameter(format!("Route: {}", route)));
-                }
-            }
-            url.pop();
+            let routes: Vec<String> = routes.iter().map(|r| r.to_string()).collect();
+            url.push_str(&routes.join(","));
         }
         if let Some(aircraft) = &params.aircraft {
             url.push_str("&aircraft=");
-            for aircraft_iter in aircraft {
-                if aircraft_iter
-                    .chars()
-                    .all(|c| c.is_alphanumeric() || c == '*')
-                {
-                    if aircraft_iter.chars().filter(|c| c == &'*').count() == 1 {
-                        url.push_str(&aircraft_iter.to_string());
-                        url.push(',');
-                    }
-                } else {
-                    return Err(FlightRadarError::Parameter(format!(
-                        "Aircraft: {}",
-                        aircraft_iter
-                    )));
-                }
-            }
-            url.pop();
+            let aircraft: Vec<String> = aircraft.iter().flat_map(|a| a.api_values()).collect();
+            url.push_str(&aircraft.join(","));
         }
         if let Some(altitude_ranges) = &params.altitude_ranges {
             url.push_str("&altitude_ranges=");
@@ -311,7 +355,7 @@ impl FlightRadarClient {
     ///   * `query_in` - FullLiveFlightQuery to check
     /// # Returns
     ///   A `bool` based on check
-    fn check_historic_parameters(query_in: &FullLiveFlightQuery) -> bool {
+    pub(crate) fn check_historic_parameters(query_in: &FullLiveFlightQuery) -> bool {
         if query_in.aircraft.is_some()
             || query_in.airports.is_some()
             || query_in.altitude_ranges.is_some()
@@ -340,14 +384,100 @@ impl FlightRadarClient {
     /// # Returns
     ///   A `String` on success and `FlightRadarError` on failure.
     pub fn query_endpoint(&self, url: String) -> Result<String, FlightRadarError> {
+        self.query_endpoint_with_status(url).map(|(_, text)| text)
+    }
+
+    /// Issue the GET command to API Endpoint, keeping the HTTP status
+    /// # Arguments
+    ///   * `url` - API URL to send GET request to
+    /// # Returns
+    ///   The status code and body on success and `FlightRadarError` on failure.
+    pub fn query_endpoint_with_status(
+        &self,
+        url: String,
+    ) -> Result<(u16, String), FlightRadarError> {
+        self.query_endpoint_with_source(url)
+            .map(|(status, text, _)| (status, text))
+    }
+
+    /// Issue the GET command to API Endpoint, keeping the HTTP status and
+    /// where the response came from
+    /// # Arguments
+    ///   * `url` - API URL to send GET request to
+    /// # Returns
+    ///   The status code, body and `ResponseSource` on success and `FlightRadarError` on failure.
+    pub fn query_endpoint_with_source(
+        &self,
+        url: String,
+    ) -> Result<(u16, String, ResponseSource), FlightRadarError> {
+        if let Some(cassette) = &self.cassette {
+            if cassette.mode() == CassetteMode::Replay {
+                let (status, text) =
+                    cassette.replay_response(&scrub_api_key(&url, &self.api_key))?;
+                return Ok((status, text, ResponseSource::Cassette));
+            }
+        }
+
+        let kind = EndpointKind::from_url(&url, &self.base_url);
+        if let Some(cached) = self.cache.as_ref().and_then(|c| c.lookup(&url, kind)) {
+            // Cache hits are recorded too, a replay runs without the cache
+            self.record_interaction(&url, 200, &cached)?;
+            return Ok((200, cached, ResponseSource::Cache));
+        }
+
+        if let Some(limiter) = &self.rate_limiter {
+            limiter.acquire();
+        }
         let response = self
             .client
             .get(&url)
             .header("Accept-Version", "v1")
             .bearer_auth(&self.api_key)
             .send()?; // synchronous send
+        let status = response.status();
         let response_text = response.text()?; // synchronous text retrieval
-        Ok(response_text)
+
+        self.record_interaction(&url, status.as_u16(), &response_text)?;
+
+        // Only successful responses are worth keeping
+        if let (Some(cache), true) = (&self.cache, status.is_success()) {
+            cache.store(&url, kind, &response_text);
+        }
+        Ok((status.as_u16(), response_text, ResponseSource::Network))
+    }
+
+    /// GET for the `static` lookups. A 404 or empty response fails as
+    /// `Parameter`, the code is unknown to FR24. Any other unsuccessful
+    /// status (bad key, rate limit, server error) fails as `General` instead
+    /// of reaching the parser.
+    fn query_static(&self, url: String) -> Result<String, FlightRadarError> {
+        match self.query_endpoint_with_status(url) {
+            Ok((404, _)) => Err(FlightRadarError::Parameter("Unknown Code".to_string())),
+            Ok((status, _)) if !(200..300).contains(&status) => Err(FlightRadarError::General(
+                format!("GET Request Failed: HTTP {}", status),
+            )),
+            Ok((_, text)) if matches!(text.trim(), "" | "null" | "{}") => {
+                Err(FlightRadarError::Parameter("Unknown Code".to_string()))
+            }
+            Ok((_, text)) => Ok(text),
+            Err(_) => Err(FlightRadarError::General("GET Request Failed".to_string())),
+        }
+    }
+
+    fn record_interaction(
+        &self,
+        url: &str,
+        status: u16,
+        body: &str,
+    ) -> Result<(), FlightRadarError> {
+        if let Some(cassette) = &self.cassette {
+            cassette.record_interaction(
+                &scrub_api_key(url, &self.api_key),
+                status,
+                &scrub_api_key(body, &self.api_key),
+            )?;
+        }
+        Ok(())
     }
 
     /// Fetches airline information by ICAO.
@@ -358,10 +488,7 @@ impl FlightRadarClient {
     pub fn get_airline_by_icao(&self, icao: &str) -> Result<Airline, FlightRadarError> {
         // Make URL and GET
         let url = format!("{}static/airlines/{}/light", self.base_url, icao);
-        let text = match self.query_endpoint(url) {
-            Ok(data) => data,
-            Err(_) => return Err(FlightRadarError::General("GET Request Failed".to_string())),
-        };
+        let text = self.query_static(url)?;
 
         // Parse
         let airline: Airline = serde_json::from_str(&text)
@@ -378,10 +505,7 @@ impl FlightRadarClient {
     pub fn get_airport_by_code(&self, code: &str) -> Result<Airport, FlightRadarError> {
         // Make URL and GET
         let url = format!("{}static/airports/{}/full", self.base_url, code);
-        let text = match self.query_endpoint(url) {
-            Ok(data) => data,
-            Err(_) => return Err(FlightRadarError::General("GET Request Failed".to_string())),
-        };
+        let text = self.query_static(url)?;
 
         // Parse
         let airport: Airport = serde_json::from_str(&text)
@@ -398,10 +522,7 @@ impl FlightRadarClient {
     pub fn get_airport_lite_by_code(&self, code: &str) -> Result<AirportLite, FlightRadarError> {
         // Make URL and GET
         let url = format!("{}static/airports/{}/light", self.base_url, code);
-        let text = match self.query_endpoint(url) {
-            Ok(data) => data,
-            Err(_) => return Err(FlightRadarError::General("GET Request Failed".to_string())),
-        };
+        let text = self.query_static(url)?;
 
         // Parse
         let airport: AirportLite = serde_json::from_str(&text)
@@ -410,6 +531,102 @@ impl FlightRadarClient {
         Ok(airport)
     }
 
+    /// Fetches several airports, `DEFAULT_BATCH_CONCURRENCY` at a time.
+    /// # Arguments
+    ///   * `codes` - Airport codes, duplicates are fetched once
+    /// # Returns
+    ///   Each upper cased code mapped to its `Airport` or `FlightRadarError`
+    pub fn get_airports<S: AsRef<str>>(
+        &self,
+        codes: &[S],
+    ) -> BTreeMap<String, Result<Airport, FlightRadarError>> {
+        self.get_airports_with_concurrency(codes, DEFAULT_BATCH_CONCURRENCY)
+    }
+
+    /// Fetches several airports with bounded concurrency.
+    ///
+    /// Requests still pass through the cache and rate limit, so a limit set
+    /// with `set_rate_limit` holds across all workers.
+    /// # Arguments
+    ///   * `codes` - Airport codes, duplicates are fetched once
+    ///   * `concurrency` - Maximum number of requests in flight
+    /// # Returns
+    ///   Each upper cased code mapped to its `Airport` or `FlightRadarError`
+    pub fn get_airports_with_concurrency<S: AsRef<str>>(
+        &self,
+        codes: &[S],
+        concurrency: usize,
+    ) -> BTreeMap<String, Result<Airport, FlightRadarError>> {
+        self.batch_lookup(codes, concurrency, Self::get_airport_by_code)
+    }
+
+    /// Fetches several airlines, `DEFAULT_BATCH_CONCURRENCY` at a time.
+    /// # Arguments
+    ///   * `icaos` - Airline ICAO codes, duplicates are fetched once
+    /// # Returns
+    ///   Each upper cased code mapped to its `Airline` or `FlightRadarError`
+    pub fn get_airlines<S: AsRef<str>>(
+        &self,
+        icaos: &[S],
+    ) -> BTreeMap<String, Result<Airline, FlightRadarError>> {
+        self.get_airlines_with_concurrency(icaos, DEFAULT_BATCH_CONCURRENCY)
+    }
+
+    /// Fetches several airlines with bounded concurrency.
+    /// # Arguments
+    ///   * `icaos` - Airline ICAO codes, duplicates are fetched once
+    ///   * `concurrency` - Maximum number of requests in flight
+    /// # Returns
+    ///   Each upper cased code mapped to its `Airline` or `FlightRadarError`
+    pub fn get_airlines_with_concurrency<S: AsRef<str>>(
+        &self,
+        icaos: &[S],
+        concurrency: usize,
+    ) -> BTreeMap<String, Result<Airline, FlightRadarError>> {
+        self.batch_lookup(icaos, concurrency, Self::get_airline_by_icao)
+    }
+
+    /// Runs a lookup for every distinct code on a pool of scoped threads
+    fn batch_lookup<S, T, F>(
+        &self,
+        codes: &[S],
+        concurrency: usize,
+        lookup: F,
+    ) -> BTreeMap<String, Result<T, FlightRadarError>>
+    where
+        S: AsRef<str>,
+        T: Send,
+        F: Fn(&Self, &str) -> Result<T, FlightRadarError> + Sync,
+    {
+        let unique: BTreeSet<String> = codes
+            .iter()
+            .map(|c| c.as_ref().trim().to_ascii_uppercase())
+            .filter(|c| !c.is_empty())
+            .collect();
+        let unique: Vec<String> = unique.into_iter().collect();
+        let next = AtomicUsize::new(0);
+
+        let workers = concurrency.clamp(1, unique.len().max(1));
+        thread::scope(|scope| {
+            let handles: Vec<_> = (0..workers)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut results = Vec::new();
+                        while let Some(code) = unique.get(next.fetch_add(1, Ordering::Relaxed)) {
+                            results.push((code.to_string(), lookup(self, code)));
+                        }
+                        results
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .flat_map(|h| h.join().expect("batch lookup worker panicked"))
+                .collect()
+        })
+    }
+
     /// Fetches live flight information by location (or other parameters).
     /// # Arguments
     ///   * `other_queries` - Optional parameters to narrow down data
@@ -500,7 +717,6 @@ impl FlightRadarClient {
         other_queries: Option<&FullLiveFlightQuery>,
     ) -> Result<FullLiveFlightResponse, FlightRadarError> {
         // Check Timestamp
-        const MIN_TIMESTAMP: u64 = 1462924800;
         if timestamp < &MIN_TIMESTAMP {
             return Err(FlightRadarError::Parameter(format!(
                 "Invalid Timestamp: {}",
@@ -560,7 +776,6 @@ impl FlightRadarClient {
         other_queries: Option<&FullLiveFlightQuery>,
     ) -> Result<LightLiveFlightResponse, FlightRadarError> {
         // Check Timestamp
-        const MIN_TIMESTAMP: u64 = 1462924800;
         if timestamp < &MIN_TIMESTAMP {
             return Err(FlightRadarError::Parameter(format!(
                 "Invalid Timestamp: {}",
@@ -670,22 +885,38 @@ impl FlightRadarClient {
 }
 
 /// Individual Tracks for flight-tracks endpoint
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Deserialize, Serialize, Default, Clone)]
 pub struct Track {
     pub timestamp: String,
     pub lat: f64,
     pub lon: f64,
     pub alt: u32,
     pub gspeed: u32,
-    pub vspeed: u32,
+    pub vspeed: i32,
     pub track: u32,
     pub squawk: String,
     pub callsign: String,
     pub source: String,
 }
 
+impl Track {
+    /// Parse the track timestamp into a UTC date time
+    /// # Returns
+    ///   A `DateTime<Utc>` on success or a `FlightRadarError` on failure.
+    pub fn parsed_timestamp(&self) -> Result<DateTime<Utc>, FlightRadarError> {
+        self.timestamp.parse().map_err(|e| {
+            FlightRadarError::Parsing(format!("Timestamp: {} ({})", self.timestamp, e))
+        })
+    }
+
+    /// Whether the point is on the ground, see `geo::on_ground`
+    pub fn on_ground(&self) -> bool {
+        crate::geo::on_ground(self.alt, self.gspeed)
+    }
+}
+
 /// Wrapper struct for flight-tracks endpoint
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Deserialize, Serialize, Default, Clone)]
 pub struct Flight {
     #[serde(rename = "fr24_id")]
     pub id: String,
@@ -693,13 +924,13 @@ pub struct Flight {
 }
 
 /// Wrapper struct of usage endpoint
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Deserialize, Serialize, Default)]
 pub struct ApiUsageResponse {
     pub data: Vec<ApiEndpointUsage>,
 }
 
 /// Individual endpoint usage data
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Deserialize, Serialize, Default)]
 pub struct ApiEndpointUsage {
     pub endpoint: String,
     pub metadata: String,
@@ -709,7 +940,7 @@ pub struct ApiEndpointUsage {
 }
 
 /// Basic Airline stucture
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
 pub struct Airline {
     pub name: String,
     pub iata: Option<String>,
@@ -717,7 +948,7 @@ pub struct Airline {
 }
 
 /// Result airport data from airport/full endpoint
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
 pub struct Airport {
     pub name: String,
     pub iata: String,
@@ -732,21 +963,21 @@ pub struct Airport {
 }
 
 /// Nested Struct for Country Data
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
 pub struct Country {
     pub code: String,
     pub name: String,
 }
 
 /// Nested Struct for Timezone Data
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
 pub struct Timezone {
     pub name: String,
     pub offset: i32,
 }
 
 /// Result airport data from airport/light endpoint
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Deserialize, Serialize, Default)]
 pub struct AirportLite {
     pub name: String,
     pub iata: String,
@@ -754,7 +985,7 @@ pub struct AirportLite {
 }
 
 /// Represents a query for flight positions.
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Deserialize, Default, Clone)]
 pub struct FullLiveFlightQuery {
     pub bounds: Option<Bounds>,
     pub flights: Option<Vec<String>>,
@@ -762,9 +993,9 @@ pub struct FullLiveFlightQuery {
     pub registrations: Option<Vec<String>>,
     pub painted_as: Option<Vec<String>>,
     pub operating_as: Option<Vec<String>>,
-    pub airports: Option<Vec<String>>,
-    pub routes: Option<Vec<String>>,
-    pub aircraft: Option<Vec<String>>,
+    pub airports: Option<Vec<AirportFilter>>,
+    pub routes: Option<Vec<RouteFilter>>,
+    pub aircraft: Option<Vec<AircraftTypeFilter>>,
     pub altitude_ranges: Option<Vec<ApiRange>>,
     pub squawks: Option<Vec<u16>>,
     pub categories: Option<Vec<char>>,
@@ -775,7 +1006,7 @@ pub struct FullLiveFlightQuery {
 }
 
 /// Represents a geographic bounding box.
-#[derive(Debug, Deserialize, Default, Serialize)]
+#[derive(Debug, Deserialize, Default, Serialize, Clone)]
 pub struct Bounds {
     pub north: f64,
     pub south: f64,
@@ -784,27 +1015,27 @@ pub struct Bounds {
 }
 
 /// Represents a numeric range with a minimum and maximum.
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Deserialize, Default, Clone)]
 pub struct ApiRange {
     pub min: u32,
     pub max: u32,
 }
 
 /// Allow range to be specific number or range
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone)]
 pub enum ApiRangeEnum {
     U32(u32),
     ApiRange(ApiRange),
 }
 
 /// Wrapper struct for flight-positions endpoint
-#[derive(Deserialize, Debug, Default)]
+#[derive(Deserialize, Serialize, Debug, Default)]
 pub struct FullLiveFlightResponse {
     pub data: Vec<FullLiveFlightData>,
 }
 
 /// Data for each flight returned from flight-positions endpoint
-#[derive(Deserialize, Debug, Default)]
+#[derive(Deserialize, Serialize, Debug, Default)]
 pub struct FullLiveFlightData {
     pub fr24_id: String,
     pub flight: String,
@@ -833,13 +1064,13 @@ pub struct FullLiveFlightData {
 }
 
 /// Data for light flight responses
-#[derive(Deserialize, Debug, Default)]
+#[derive(Deserialize, Serialize, Debug, Default)]
 pub struct LightLiveFlightResponse {
     pub data: Vec<LightLiveFlightData>,
 }
 
 /// Data for each flight returned from flight-positions endpoint
-#[derive(Deserialize, Debug, Default)]
+#[derive(Deserialize, Serialize, Debug, Default)]
 pub struct LightLiveFlightData {
     pub fr24_id: String,
     pub hex: String,
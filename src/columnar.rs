@@ -0,0 +1,285 @@
+/// Arrow `RecordBatch` conversion and partitioned Parquet output for API data
+use crate::client::{Flight, FullLiveFlightResponse, LightLiveFlightResponse};
+use crate::error::FlightRadarError;
+use arrow::array::{
+    Array, ArrayRef, Float64Array, Int32Array, StringArray, TimestampMillisecondArray, UInt32Array,
+};
+use arrow::compute::take_record_batch;
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use arrow::record_batch::RecordBatch;
+use chrono::{DateTime, Utc};
+use parquet::arrow::ArrowWriter;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Partition value used for rows without a parsable timestamp
+const DEFAULT_PARTITION: &str = "__HIVE_DEFAULT_PARTITION__";
+
+/// Converts a `FullLiveFlightResponse` into a `RecordBatch` with one row per flight
+/// # Arguments
+///   * `response` - Full live or historic snapshot
+/// # Returns
+///   A `RecordBatch` on success or a `FlightRadarError` on failure.
+pub fn full_live_to_record_batch(
+    response: &FullLiveFlightResponse,
+) -> Result<RecordBatch, FlightRadarError> {
+    let data = &response.data;
+    let columns: Vec<ArrayRef> = vec![
+        strings(data.iter().map(|d| d.fr24_id.as_str())),
+        strings(data.iter().map(|d| d.flight.as_str())),
+        strings(data.iter().map(|d| d.callsign.as_str())),
+        Arc::new(Float64Array::from_iter_values(data.iter().map(|d| d.lat))),
+        Arc::new(Float64Array::from_iter_values(data.iter().map(|d| d.lon))),
+        Arc::new(UInt32Array::from_iter_values(data.iter().map(|d| d.track))),
+        Arc::new(UInt32Array::from_iter_values(data.iter().map(|d| d.alt))),
+        Arc::new(UInt32Array::from_iter_values(data.iter().map(|d| d.gspeed))),
+        Arc::new(Int32Array::from_iter_values(data.iter().map(|d| d.vspeed))),
+        strings(data.iter().map(|d| d.squawk.as_str())),
+        timestamps(data.iter().map(|d| d.timestamp.as_str())),
+        strings(data.iter().map(|d| d.source.as_str())),
+        strings(data.iter().map(|d| d.hex.as_str())),
+        strings(data.iter().map(|d| d.type_field.as_str())),
+        strings(data.iter().map(|d| d.reg.as_str())),
+        strings(data.iter().map(|d| d.painted_as.as_str())),
+        strings(data.iter().map(|d| d.operating_as.as_str())),
+        strings(data.iter().map(|d| d.orig_iata.as_str())),
+        strings(data.iter().map(|d| d.orig_icao.as_str())),
+        strings(data.iter().map(|d| d.dest_iata.as_str())),
+        strings(data.iter().map(|d| d.dest_icao.as_str())),
+        timestamps(data.iter().map(|d| d.eta.as_str())),
+    ];
+
+    RecordBatch::try_new(full_live_schema(), columns).map_err(columnar_error)
+}
+
+/// Converts a `LightLiveFlightResponse` into a `RecordBatch` with one row per flight
+/// # Arguments
+///   * `response` - Light live or historic snapshot
+/// # Returns
+///   A `RecordBatch` on success or a `FlightRadarError` on failure.
+pub fn light_live_to_record_batch(
+    response: &LightLiveFlightResponse,
+) -> Result<RecordBatch, FlightRadarError> {
+    let data = &response.data;
+    let columns: Vec<ArrayRef> = vec![
+        strings(data.iter().map(|d| d.fr24_id.as_str())),
+        strings(data.iter().map(|d| d.hex.as_str())),
+        strings(data.iter().map(|d| d.callsign.as_str())),
+        Arc::new(Float64Array::from_iter_values(data.iter().map(|d| d.lat))),
+        Arc::new(Float64Array::from_iter_values(data.iter().map(|d| d.lon))),
+        Arc::new(UInt32Array::from_iter_values(data.iter().map(|d| d.track))),
+        Arc::new(UInt32Array::from_iter_values(data.iter().map(|d| d.alt))),
+        Arc::new(UInt32Array::from_iter_values(data.iter().map(|d| d.gspeed))),
+        Arc::new(Int32Array::from_iter_values(data.iter().map(|d| d.vspeed))),
+        strings(data.iter().map(|d| d.squawk.as_str())),
+        timestamps(data.iter().map(|d| d.timestamp.as_str())),
+        strings(data.iter().map(|d| d.source.as_str())),
+    ];
+
+    RecordBatch::try_new(light_live_schema(), columns).map_err(columnar_error)
+}
+
+/// Converts flight tracks into a `RecordBatch` with one row per track point
+/// # Arguments
+///   * `flights` - Flights returned from the `flight-tracks` endpoint
+/// # Returns
+///   A `RecordBatch` on success or a `FlightRadarError` on failure.
+pub fn flights_to_record_batch(flights: &[Flight]) -> Result<RecordBatch, FlightRadarError> {
+    let points: Vec<_> = flights
+        .iter()
+        .flat_map(|f| f.tracks.iter().map(move |t| (f.id.as_str(), t)))
+        .collect();
+    let columns: Vec<ArrayRef> = vec![
+        strings(points.iter().map(|(id, _)| *id)),
+        timestamps(points.iter().map(|(_, t)| t.timestamp.as_str())),
+        Arc::new(Float64Array::from_iter_values(
+            points.iter().map(|(_, t)| t.lat),
+        )),
+        Arc::new(Float64Array::from_iter_values(
+            points.iter().map(|(_, t)| t.lon),
+        )),
+        Arc::new(UInt32Array::from_iter_values(
+            points.iter().map(|(_, t)| t.alt),
+        )),
+        Arc::new(UInt32Array::from_iter_values(
+            points.iter().map(|(_, t)| t.gspeed),
+        )),
+        Arc::new(Int32Array::from_iter_values(
+            points.iter().map(|(_, t)| t.vspeed),
+        )),
+        Arc::new(UInt32Array::from_iter_values(
+            points.iter().map(|(_, t)| t.track),
+        )),
+        strings(points.iter().map(|(_, t)| t.squawk.as_str())),
+        strings(points.iter().map(|(_, t)| t.callsign.as_str())),
+        strings(points.iter().map(|(_, t)| t.source.as_str())),
+    ];
+
+    RecordBatch::try_new(flights_schema(), columns).map_err(columnar_error)
+}
+
+/// Arrow schema produced by `full_live_to_record_batch`
+pub fn full_live_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("fr24_id", DataType::Utf8, false),
+        Field::new("flight", DataType::Utf8, false),
+        Field::new("callsign", DataType::Utf8, false),
+        Field::new("lat", DataType::Float64, false),
+        Field::new("lon", DataType::Float64, false),
+        Field::new("track", DataType::UInt32, false),
+        Field::new("alt", DataType::UInt32, false),
+        Field::new("gspeed", DataType::UInt32, false),
+        Field::new("vspeed", DataType::Int32, false),
+        Field::new("squawk", DataType::Utf8, false),
+        Field::new("timestamp", timestamp_type(), true),
+        Field::new("source", DataType::Utf8, false),
+        Field::new("hex", DataType::Utf8, false),
+        Field::new("type", DataType::Utf8, false),
+        Field::new("reg", DataType::Utf8, false),
+        Field::new("painted_as", DataType::Utf8, false),
+        Field::new("operating_as", DataType::Utf8, false),
+        Field::new("orig_iata", DataType::Utf8, false),
+        Field::new("orig_icao", DataType::Utf8, false),
+        Field::new("dest_iata", DataType::Utf8, false),
+        Field::new("dest_icao", DataType::Utf8, false),
+        Field::new("eta", timestamp_type(), true),
+    ]))
+}
+
+/// Arrow schema produced by `light_live_to_record_batch`
+pub fn light_live_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("fr24_id", DataType::Utf8, false),
+        Field::new("hex", DataType::Utf8, false),
+        Field::new("callsign", DataType::Utf8, false),
+        Field::new("lat", DataType::Float64, false),
+        Field::new("lon", DataType::Float64, false),
+        Field::new("track", DataType::UInt32, false),
+        Field::new("alt", DataType::UInt32, false),
+        Field::new("gspeed", DataType::UInt32, false),
+        Field::new("vspeed", DataType::Int32, false),
+        Field::new("squawk", DataType::Utf8, false),
+        Field::new("timestamp", timestamp_type(), true),
+        Field::new("source", DataType::Utf8, false),
+    ]))
+}
+
+/// Arrow schema produced by `flights_to_record_batch`
+pub fn flights_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("fr24_id", DataType::Utf8, false),
+        Field::new("timestamp", timestamp_type(), true),
+        Field::new("lat", DataType::Float64, false),
+        Field::new("lon", DataType::Float64, false),
+        Field::new("alt", DataType::UInt32, false),
+        Field::new("gspeed", DataType::UInt32, false),
+        Field::new("vspeed", DataType::Int32, false),
+        Field::new("track", DataType::UInt32, false),
+        Field::new("squawk", DataType::Utf8, false),
+        Field::new("callsign", DataType::Utf8, false),
+        Field::new("source", DataType::Utf8, false),
+    ]))
+}
+
+/// Writes record batches into Hive style `date=YYYY-MM-DD/hour=HH` Parquet partitions
+pub struct ParquetPartitionWriter {
+    root: PathBuf,
+    files_written: usize,
+}
+
+impl ParquetPartitionWriter {
+    /// Creates a new writer.
+    /// # Arguments
+    ///   * `root` - Directory that holds the partition folders
+    pub fn new<P: Into<PathBuf>>(root: P) -> Self {
+        ParquetPartitionWriter {
+            root: root.into(),
+            files_written: 0,
+        }
+    }
+
+    /// Splits a batch by its `timestamp` column and writes one file per partition.
+    /// # Arguments
+    ///   * `batch` - Batch produced by one of the `*_to_record_batch` functions
+    /// # Returns
+    ///   Paths of the written files on success or a `FlightRadarError` on failure.
+    pub fn write(&mut self, batch: &RecordBatch) -> Result<Vec<PathBuf>, FlightRadarError> {
+        let timestamps = batch
+            .column_by_name("timestamp")
+            .and_then(|c| c.as_any().downcast_ref::<TimestampMillisecondArray>())
+            .ok_or_else(|| {
+                FlightRadarError::Parameter("Batch Missing timestamp Column".to_string())
+            })?;
+
+        // Group row indices by partition
+        let mut partitions: BTreeMap<(String, String), Vec<u32>> = BTreeMap::new();
+        for row in 0..batch.num_rows() {
+            let time = if timestamps.is_null(row) {
+                None
+            } else {
+                DateTime::<Utc>::from_timestamp_millis(timestamps.value(row))
+            };
+            let key = match time {
+                Some(time) => (
+                    time.format("%Y-%m-%d").to_string(),
+                    time.format("%H").to_string(),
+                ),
+                None => (DEFAULT_PARTITION.to_string(), DEFAULT_PARTITION.to_string()),
+            };
+            partitions.entry(key).or_default().push(row as u32);
+        }
+
+        let mut written = Vec::new();
+        for ((date, hour), rows) in partitions {
+            let part =
+                take_record_batch(batch, &UInt32Array::from(rows)).map_err(columnar_error)?;
+
+            let dir = self
+                .root
+                .join(format!("date={}", date))
+                .join(format!("hour={}", hour));
+            fs::create_dir_all(&dir)?;
+            let path = dir.join(format!(
+                "part-{}-{:05}.parquet",
+                Utc::now().timestamp_millis(),
+                self.files_written
+            ));
+
+            let mut writer = ArrowWriter::try_new(File::create(&path)?, part.schema(), None)
+                .map_err(columnar_error)?;
+            writer.write(&part).map_err(columnar_error)?;
+            writer.close().map_err(columnar_error)?;
+
+            self.files_written += 1;
+            written.push(path);
+        }
+
+        Ok(written)
+    }
+}
+
+fn timestamp_type() -> DataType {
+    DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into()))
+}
+
+fn strings<'a>(values: impl Iterator<Item = &'a str>) -> ArrayRef {
+    Arc::new(StringArray::from_iter_values(values))
+}
+
+/// Timestamp column from API strings, unparsable or empty values become null
+fn timestamps<'a>(values: impl Iterator<Item = &'a str>) -> ArrayRef {
+    let parsed: Vec<Option<i64>> = values
+        .map(|v| {
+            v.parse::<DateTime<Utc>>()
+                .ok()
+                .map(|t| t.timestamp_millis())
+        })
+        .collect();
+    Arc::new(TimestampMillisecondArray::from(parsed).with_timezone("UTC"))
+}
+
+fn columnar_error<E: std::fmt::Display>(err: E) -> FlightRadarError {
+    FlightRadarError::General(format!("Columnar Output: {}", err))
+}
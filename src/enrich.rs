@@ -0,0 +1,201 @@
+/// Joins live flights with airport and airline details
+use crate::client::{
+    Airline, Airport, FlightRadarClient, FullLiveFlightData, FullLiveFlightResponse,
+    DEFAULT_BATCH_CONCURRENCY,
+};
+use crate::error::FlightRadarError;
+use crate::geo;
+use std::collections::{BTreeSet, HashMap};
+
+/// Live flight with the details of its airports and airlines
+#[derive(Debug, Clone)]
+pub struct EnrichedFlight<'a> {
+    pub flight: &'a FullLiveFlightData,
+    pub origin: Option<Airport>,
+    pub destination: Option<Airport>,
+    /// Airline from `operating_as`
+    pub operating_airline: Option<Airline>,
+    /// Airline from `painted_as`
+    pub painted_airline: Option<Airline>,
+    /// Great-circle distance from origin to destination in nautical miles
+    pub route_distance_nm: Option<f64>,
+    /// Great-circle distance from the current position to the destination in nautical miles
+    pub remaining_distance_nm: Option<f64>,
+}
+
+impl EnrichedFlight<'_> {
+    /// Share of the route already flown, between `0.0` and `1.0`
+    pub fn progress(&self) -> Option<f64> {
+        match (self.route_distance_nm, self.remaining_distance_nm) {
+            (Some(route), Some(remaining)) if route > 0.0 => {
+                Some((1.0 - remaining / route).clamp(0.0, 1.0))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Attaches airports and airlines to live flights, remembering every lookup
+/// so repeated snapshots only fetch codes not seen before
+pub struct Enricher<'c> {
+    client: &'c FlightRadarClient,
+    concurrency: usize,
+    airports: HashMap<String, Option<Airport>>,
+    airlines: HashMap<String, Option<Airline>>,
+}
+
+impl<'c> Enricher<'c> {
+    /// Creates an enricher fetching through a client.
+    /// # Arguments
+    ///   * `client` - Client used for airport and airline lookups
+    /// # Returns
+    ///   An `Enricher` with no remembered lookups
+    pub fn new(client: &'c FlightRadarClient) -> Self {
+        Enricher {
+            client,
+            concurrency: DEFAULT_BATCH_CONCURRENCY,
+            airports: HashMap::new(),
+            airlines: HashMap::new(),
+        }
+    }
+
+    /// Sets the number of lookups in flight at once
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    /// Adds an airport without fetching it, for instance from a local store
+    pub fn insert_airport(&mut self, airport: Airport) {
+        for code in [&airport.icao, &airport.iata] {
+            if !code.is_empty() {
+                self.airports
+                    .insert(code.to_ascii_uppercase(), Some(airport.clone()));
+            }
+        }
+    }
+
+    /// Adds an airline without fetching it, for instance from a local store
+    pub fn insert_airline(&mut self, airline: Airline) {
+        self.airlines
+            .insert(airline.icao.to_ascii_uppercase(), Some(airline));
+    }
+
+    /// Codes FR24 doesn't know, they are not retried. Every other failure
+    /// (network, API key, rate limit, unreadable response) is not listed and
+    /// is retried by the next `enrich` call.
+    pub fn failed_lookups(&self) -> BTreeSet<&str> {
+        let airports = self.airports.iter().filter(|(_, a)| a.is_none());
+        let airlines = self.airlines.iter().filter(|(_, a)| a.is_none());
+        airports
+            .map(|(code, _)| code.as_str())
+            .chain(airlines.map(|(code, _)| code.as_str()))
+            .collect()
+    }
+
+    /// Enriches every flight of a snapshot.
+    ///
+    /// Airports are looked up by ICAO code, falling back to IATA when FR24 has
+    /// no ICAO code. Unknown or failed lookups leave the field `None`.
+    /// # Arguments
+    ///   * `response` - Snapshot from the `flight-positions` endpoint
+    /// # Returns
+    ///   One `EnrichedFlight` per aircraft, in snapshot order
+    pub fn enrich<'a>(&mut self, response: &'a FullLiveFlightResponse) -> Vec<EnrichedFlight<'a>> {
+        self.fetch_missing(&response.data);
+
+        response
+            .data
+            .iter()
+            .map(|flight| {
+                let origin = self.airport(&flight.orig_icao, &flight.orig_iata);
+                let destination = self.airport(&flight.dest_icao, &flight.dest_iata);
+                let route_distance_nm = origin
+                    .zip(destination)
+                    .map(|(o, d)| geo::haversine_nm(o.lat, o.lon, d.lat, d.lon));
+                let remaining_distance_nm =
+                    destination.map(|d| geo::haversine_nm(flight.lat, flight.lon, d.lat, d.lon));
+
+                EnrichedFlight {
+                    flight,
+                    origin: origin.cloned(),
+                    destination: destination.cloned(),
+                    operating_airline: self.airline(&flight.operating_as).cloned(),
+                    painted_airline: self.airline(&flight.painted_as).cloned(),
+                    route_distance_nm,
+                    remaining_distance_nm,
+                }
+            })
+            .collect()
+    }
+
+    fn fetch_missing(&mut self, flights: &[FullLiveFlightData]) {
+        let mut airport_codes = BTreeSet::new();
+        let mut airline_codes = BTreeSet::new();
+        for flight in flights {
+            for (icao, iata) in [
+                (&flight.orig_icao, &flight.orig_iata),
+                (&flight.dest_icao, &flight.dest_iata),
+            ] {
+                if let Some(code) = airport_key(icao, iata) {
+                    if !self.airports.contains_key(&code) {
+                        airport_codes.insert(code);
+                    }
+                }
+            }
+            for code in [&flight.operating_as, &flight.painted_as] {
+                let code = code.trim().to_ascii_uppercase();
+                if !code.is_empty() && !self.airlines.contains_key(&code) {
+                    airline_codes.insert(code);
+                }
+            }
+        }
+
+        let airport_codes: Vec<String> = airport_codes.into_iter().collect();
+        for (code, result) in self
+            .client
+            .get_airports_with_concurrency(&airport_codes, self.concurrency)
+        {
+            if let Some(airport) = lookup_outcome(result) {
+                self.airports.insert(code, airport);
+            }
+        }
+
+        let airline_codes: Vec<String> = airline_codes.into_iter().collect();
+        for (code, result) in self
+            .client
+            .get_airlines_with_concurrency(&airline_codes, self.concurrency)
+        {
+            if let Some(airline) = lookup_outcome(result) {
+                self.airlines.insert(code, airline);
+            }
+        }
+    }
+
+    fn airport(&self, icao: &str, iata: &str) -> Option<&Airport> {
+        self.airports.get(&airport_key(icao, iata)?)?.as_ref()
+    }
+
+    fn airline(&self, icao: &str) -> Option<&Airline> {
+        self.airlines
+            .get(&icao.trim().to_ascii_uppercase())?
+            .as_ref()
+    }
+}
+
+/// Lookup result worth remembering, `None` for failures that may pass on retry
+fn lookup_outcome<T>(result: Result<T, FlightRadarError>) -> Option<Option<T>> {
+    match result {
+        Ok(value) => Some(Some(value)),
+        // Only a 404 or empty response, see `FlightRadarClient::query_static`
+        Err(FlightRadarError::Parameter(_)) => Some(None),
+        Err(_) => None,
+    }
+}
+
+fn airport_key(icao: &str, iata: &str) -> Option<String> {
+    [icao, iata]
+        .into_iter()
+        .map(|c| c.trim().to_ascii_uppercase())
+        .find(|c| !c.is_empty())
+}
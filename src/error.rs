@@ -2,6 +2,7 @@ use std::fmt;
 
 /// Crate Error Types that may arise.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum FlightRadarError {
     /// Errors returned by the HTTP client.
     Http(reqwest::Error),
@@ -11,6 +12,8 @@ pub enum FlightRadarError {
     General(String),
     /// Invalid Parameter Passed to API.
     Parameter(String),
+    /// Errors reading or writing local files.
+    Io(std::io::Error),
 }
 
 impl fmt::Display for FlightRadarError {
@@ -20,6 +23,7 @@ impl fmt::Display for FlightRadarError {
             FlightRadarError::Parsing(msg) => write!(f, "Parsing Error: {}", msg),
             FlightRadarError::General(msg) => write!(f, "Error: {}", msg),
             FlightRadarError::Parameter(msg) => write!(f, "Invalid Parameter: {}", msg),
+            FlightRadarError::Io(err) => write!(f, "IO Error: {}", err),
         }
     }
 }
@@ -28,6 +32,7 @@ impl std::error::Error for FlightRadarError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
             FlightRadarError::Http(err) => Some(err),
+            FlightRadarError::Io(err) => Some(err),
             _ => None,
         }
     }
@@ -38,3 +43,9 @@ impl From<reqwest::Error> for FlightRadarError {
         FlightRadarError::Http(err)
     }
 }
+
+impl From<std::io::Error> for FlightRadarError {
+    fn from(err: std::io::Error) -> Self {
+        FlightRadarError::Io(err)
+    }
+}
@@ -0,0 +1,206 @@
+/// Arrival time estimation from position, speed and track history
+use crate::client::{Airport, Flight, FullLiveFlightData};
+use crate::enrich::EnrichedFlight;
+use crate::error::FlightRadarError;
+use crate::geo;
+use chrono::{DateTime, Duration, Utc};
+
+/// Parameters of the cruise, descent and approach model
+#[derive(Debug, Clone, PartialEq)]
+pub struct EtaModel {
+    /// Track distance flown per 1000 ft of descent, 3 nm matches a 3 degree path
+    pub descent_nm_per_1000ft: f64,
+    /// Length of the final approach in nautical miles
+    pub approach_distance_nm: f64,
+    /// Ground speed on final approach in knots
+    pub approach_speed_kt: f64,
+    /// Time added for vectoring and sequencing before the approach
+    pub arrival_overhead: Duration,
+    /// Track history considered when estimating the cruise speed
+    pub history_window: Duration,
+    /// Fixed part of the confidence band
+    pub base_uncertainty: Duration,
+    /// Part of the confidence band growing with the time to go
+    pub relative_uncertainty: f64,
+}
+
+impl Default for EtaModel {
+    fn default() -> Self {
+        EtaModel {
+            descent_nm_per_1000ft: 3.0,
+            approach_distance_nm: 10.0,
+            approach_speed_kt: 150.0,
+            arrival_overhead: Duration::minutes(3),
+            history_window: Duration::minutes(10),
+            base_uncertainty: Duration::minutes(2),
+            relative_uncertainty: 0.05,
+        }
+    }
+}
+
+/// Estimated arrival time with its confidence band
+#[derive(Debug, Clone, PartialEq)]
+pub struct EtaEstimate {
+    pub eta: DateTime<Utc>,
+    pub earliest: DateTime<Utc>,
+    pub latest: DateTime<Utc>,
+    /// Great-circle distance to the destination in nautical miles
+    pub remaining_nm: f64,
+    /// Speed used for the cruise segment in knots
+    pub cruise_speed_kt: f64,
+    /// ETA reported by FR24, when present
+    pub fr24_eta: Option<DateTime<Utc>>,
+}
+
+impl EtaEstimate {
+    /// Estimate minus FR24's ETA, positive when the estimate is later
+    pub fn fr24_difference(&self) -> Option<Duration> {
+        self.fr24_eta.map(|fr24| self.eta - fr24)
+    }
+
+    /// Whether FR24's ETA lies inside the confidence band
+    pub fn fr24_within_band(&self) -> Option<bool> {
+        self.fr24_eta
+            .map(|fr24| self.earliest <= fr24 && fr24 <= self.latest)
+    }
+}
+
+impl EtaModel {
+    /// Estimates the arrival time of a flight.
+    ///
+    /// The remaining distance is split into cruise, descent and approach.
+    /// Cruise uses the closure rate towards the destination over the track
+    /// history when available. A closure rate below half the approach speed,
+    /// as in a hold or on vectors, falls back to the current ground speed.
+    /// Descent
+    /// starts far enough out to lose the height above the airport and is flown
+    /// at the mean of cruise and approach speed. The band widens with the time
+    /// to go and with ground speed variation in the history, and extends
+    /// further late than early since holding and vectoring add time.
+    /// # Arguments
+    ///   * `flight` - Current aircraft state
+    ///   * `destination` - Destination airport
+    ///   * `history` - Recent track of the flight from the `flight-tracks` endpoint
+    /// # Returns
+    ///   An `EtaEstimate` on success or a `FlightRadarError` on failure.
+    pub fn estimate(
+        &self,
+        flight: &FullLiveFlightData,
+        destination: &Airport,
+        history: Option<&Flight>,
+    ) -> Result<EtaEstimate, FlightRadarError> {
+        let now = parse_time(&flight.timestamp)?;
+        let fr24_eta = parse_time(&flight.eta).ok();
+        let remaining_nm =
+            geo::haversine_nm(flight.lat, flight.lon, destination.lat, destination.lon);
+
+        let (history_speed, variation) = history
+            .map(|h| self.history_speed(h, now, destination))
+            .unwrap_or((None, 0.0));
+        let min_speed_kt = self.approach_speed_kt / 2.0;
+        let cruise_speed_kt = history_speed
+            .filter(|speed| *speed >= min_speed_kt)
+            .unwrap_or(flight.gspeed as f64);
+        if cruise_speed_kt < min_speed_kt {
+            return Err(FlightRadarError::Parameter(format!(
+                "ETA: {} is not airborne towards {}",
+                flight.fr24_id, destination.icao
+            )));
+        }
+
+        let height_ft = (flight.alt as f64 - destination.elevation as f64).max(0.0);
+        let approach_nm = remaining_nm.min(self.approach_distance_nm);
+        let descent_nm =
+            (remaining_nm - approach_nm).min(height_ft / 1000.0 * self.descent_nm_per_1000ft);
+        let cruise_nm = remaining_nm - approach_nm - descent_nm;
+        let descent_speed_kt = (cruise_speed_kt + self.approach_speed_kt) / 2.0;
+
+        let mut hours = cruise_nm / cruise_speed_kt
+            + descent_nm / descent_speed_kt
+            + approach_nm / self.approach_speed_kt;
+        if remaining_nm > self.approach_distance_nm {
+            hours += self.arrival_overhead.num_milliseconds() as f64 / 3_600_000.0;
+        }
+        let to_go = Duration::milliseconds((hours * 3_600_000.0).round() as i64);
+
+        let uncertainty_ms = self.base_uncertainty.num_milliseconds() as f64
+            + to_go.num_milliseconds() as f64 * (self.relative_uncertainty + variation);
+        let uncertainty = Duration::milliseconds(uncertainty_ms.round() as i64);
+
+        let eta = now + to_go;
+        Ok(EtaEstimate {
+            eta,
+            earliest: (eta - uncertainty).max(now),
+            latest: eta + uncertainty + uncertainty / 2,
+            remaining_nm,
+            cruise_speed_kt,
+            fr24_eta,
+        })
+    }
+
+    /// Closure speed towards the destination and relative ground speed
+    /// variation over the history window
+    fn history_speed(
+        &self,
+        history: &Flight,
+        now: DateTime<Utc>,
+        destination: &Airport,
+    ) -> (Option<f64>, f64) {
+        let recent: Vec<(DateTime<Utc>, f64, f64)> = history
+            .tracks
+            .iter()
+            .filter(|t| t.alt > 0)
+            .filter_map(|t| {
+                let time = t.parsed_timestamp().ok()?;
+                let distance = geo::haversine_nm(t.lat, t.lon, destination.lat, destination.lon);
+                (time <= now && now - time <= self.history_window).then_some((
+                    time,
+                    distance,
+                    t.gspeed as f64,
+                ))
+            })
+            .collect();
+
+        let Some((first, last)) = recent
+            .iter()
+            .min_by_key(|p| p.0)
+            .zip(recent.iter().max_by_key(|p| p.0))
+        else {
+            return (None, 0.0);
+        };
+
+        let speeds: Vec<f64> = recent.iter().map(|p| p.2).collect();
+        let mean = speeds.iter().sum::<f64>() / speeds.len() as f64;
+        let variation = if mean > 0.0 {
+            (speeds.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / speeds.len() as f64).sqrt()
+                / mean
+        } else {
+            0.0
+        };
+
+        let hours = (last.0 - first.0).num_milliseconds() as f64 / 3_600_000.0;
+        let closure = (first.1 - last.1) / hours;
+        // Too short a history or a track leading away gives no usable closure rate
+        let speed = (hours >= 1.0 / 60.0 && closure > 0.0).then_some(closure);
+        (speed, variation)
+    }
+}
+
+impl EnrichedFlight<'_> {
+    /// Estimates the arrival time at the destination with the default model.
+    /// # Arguments
+    ///   * `history` - Recent track of the flight from the `flight-tracks` endpoint
+    /// # Returns
+    ///   An `EtaEstimate` on success or a `FlightRadarError` on failure.
+    pub fn estimate_eta(&self, history: Option<&Flight>) -> Result<EtaEstimate, FlightRadarError> {
+        let destination = self.destination.as_ref().ok_or_else(|| {
+            FlightRadarError::Parameter(format!("ETA: {} has no destination", self.flight.fr24_id))
+        })?;
+        EtaModel::default().estimate(self.flight, destination, history)
+    }
+}
+
+fn parse_time(text: &str) -> Result<DateTime<Utc>, FlightRadarError> {
+    text.parse()
+        .map_err(|e| FlightRadarError::Parsing(format!("Timestamp {}: {}", text, e)))
+}
@@ -0,0 +1,295 @@
+/// Go-around, holding pattern and diversion detection for the `flight-tracks` API Endpoint resultant data
+use crate::client::{Airport, Flight, Track};
+use crate::geo;
+use chrono::{DateTime, Utc};
+
+/// Time and place of a track point involved in an event
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventPosition {
+    pub timestamp: DateTime<Utc>,
+    pub lat: f64,
+    pub lon: f64,
+    pub alt: u32,
+}
+
+impl EventPosition {
+    fn new(timestamp: DateTime<Utc>, track: &Track) -> Self {
+        EventPosition {
+            timestamp,
+            lat: track.lat,
+            lon: track.lon,
+            alt: track.alt,
+        }
+    }
+}
+
+/// Approach abandoned near the destination
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoAround {
+    /// Lowest point of the abandoned approach
+    pub lowest: EventPosition,
+    /// First point of the climb out above the climb threshold
+    pub climb: EventPosition,
+    /// Distance of the lowest point from the destination in nautical miles
+    pub distance_nm: f64,
+}
+
+/// Full turns flown within a small area
+#[derive(Debug, Clone, PartialEq)]
+pub struct Holding {
+    pub start: EventPosition,
+    pub end: EventPosition,
+    /// Completed 360 degree turns
+    pub turns: u32,
+    /// Mean position of the points of the pattern
+    pub center_lat: f64,
+    pub center_lon: f64,
+}
+
+impl Holding {
+    /// Time spent in the pattern
+    pub fn duration(&self) -> chrono::Duration {
+        self.end.timestamp - self.start.timestamp
+    }
+}
+
+/// Landing away from the planned destination
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diversion {
+    /// ICAO code of the planned destination
+    pub planned: String,
+    /// First point on the ground after the final landing
+    pub landing: EventPosition,
+    /// Distance of the landing from the planned destination in nautical miles
+    pub distance_nm: f64,
+}
+
+/// Event found in a flight's tracks
+#[derive(Debug, Clone, PartialEq)]
+pub enum FlightEvent {
+    GoAround(GoAround),
+    Holding(Holding),
+    Diversion(Diversion),
+}
+
+impl FlightEvent {
+    /// Time the event started
+    pub fn timestamp(&self) -> DateTime<Utc> {
+        match self {
+            FlightEvent::GoAround(g) => g.lowest.timestamp,
+            FlightEvent::Holding(h) => h.start.timestamp,
+            FlightEvent::Diversion(d) => d.landing.timestamp,
+        }
+    }
+}
+
+/// Thresholds of the event detectors
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventDetector {
+    /// Distance from the destination within which approaches are checked
+    pub approach_radius_nm: f64,
+    /// Height above the destination an approach must descend below
+    pub go_around_below_ft: f64,
+    /// Climb above the lowest point that confirms a go-around
+    pub go_around_climb_ft: f64,
+    /// Largest distance from the entry point for a turn to count as holding
+    pub holding_extent_nm: f64,
+    /// Full turns needed for a holding
+    pub holding_min_turns: u32,
+    /// Degrees short of a full turn still counted as one, for sparse tracks
+    pub holding_turn_tolerance_deg: f64,
+    /// Landing distance from the destination reported as a diversion
+    pub diversion_distance_nm: f64,
+}
+
+impl Default for EventDetector {
+    fn default() -> Self {
+        EventDetector {
+            approach_radius_nm: 10.0,
+            go_around_below_ft: 1500.0,
+            go_around_climb_ft: 500.0,
+            holding_extent_nm: 12.0,
+            holding_min_turns: 1,
+            holding_turn_tolerance_deg: 30.0,
+            diversion_distance_nm: 10.0,
+        }
+    }
+}
+
+/// Tracks in time order, skipping unreadable and repeated timestamps
+pub(crate) fn ordered_points(flight: &Flight) -> Vec<(DateTime<Utc>, &Track)> {
+    let mut points: Vec<(DateTime<Utc>, &Track)> = flight
+        .tracks
+        .iter()
+        .filter_map(|t| Some((t.parsed_timestamp().ok()?, t)))
+        .collect();
+    points.sort_by_key(|p| p.0);
+    points.dedup_by_key(|p| p.0);
+    points
+}
+
+impl EventDetector {
+    /// Finds go-arounds: a descent below `go_around_below_ft` above the
+    /// destination within `approach_radius_nm`, followed by a climb of
+    /// `go_around_climb_ft` without touching down.
+    /// # Arguments
+    ///   * `flight` - Flight whose tracks are scanned
+    ///   * `destination` - Airport the flight approaches
+    /// # Returns
+    ///   The `GoAround` events in time order
+    pub fn go_arounds(&self, flight: &Flight, destination: &Airport) -> Vec<GoAround> {
+        let points = ordered_points(flight);
+        let elevation = destination.elevation as f64;
+        let mut events = Vec::new();
+        let mut lowest: Option<(DateTime<Utc>, &Track)> = None;
+
+        for pair in points.windows(2) {
+            let (previous, (time, track)) = (pair[0], pair[1]);
+            let distance =
+                geo::haversine_nm(track.lat, track.lon, destination.lat, destination.lon);
+            if track.on_ground() || distance > self.approach_radius_nm {
+                lowest = None;
+                continue;
+            }
+
+            let height = track.alt as f64 - elevation;
+            match lowest {
+                Some((_, low)) if track.alt <= low.alt => lowest = Some((time, track)),
+                Some((low_time, low))
+                    if track.alt as f64 - low.alt as f64 >= self.go_around_climb_ft =>
+                {
+                    events.push(GoAround {
+                        lowest: EventPosition::new(low_time, low),
+                        climb: EventPosition::new(time, track),
+                        distance_nm: geo::haversine_nm(
+                            low.lat,
+                            low.lon,
+                            destination.lat,
+                            destination.lon,
+                        ),
+                    });
+                    lowest = None;
+                }
+                Some(_) => {}
+                // Only a descending aircraft starts an approach, not one climbing out
+                None if height < self.go_around_below_ft && track.alt < previous.1.alt => {
+                    lowest = Some((time, track))
+                }
+                None => {}
+            }
+        }
+        events
+    }
+
+    /// Finds holding patterns: turns adding up to full circles while the
+    /// aircraft stays within `holding_extent_nm` of where the turning began.
+    /// # Arguments
+    ///   * `flight` - Flight whose tracks are scanned
+    /// # Returns
+    ///   The `Holding` events in time order
+    pub fn holdings(&self, flight: &Flight) -> Vec<Holding> {
+        let points = ordered_points(flight);
+        let mut events = Vec::new();
+        let mut start = 0;
+
+        while start + 1 < points.len() {
+            let (_, entry) = points[start];
+            let mut turned = 0.0;
+            let mut turns = 0;
+            let mut last_turn = start;
+
+            for end in start + 1..points.len() {
+                let track = points[end].1;
+                if track.on_ground()
+                    || geo::haversine_nm(entry.lat, entry.lon, track.lat, track.lon)
+                        > self.holding_extent_nm
+                {
+                    break;
+                }
+                turned +=
+                    geo::heading_difference(points[end - 1].1.track as f64, track.track as f64);
+                let full = 360.0 * (turns + 1) as f64 - self.holding_turn_tolerance_deg;
+                if turned.abs() >= full {
+                    turns += 1;
+                    last_turn = end;
+                }
+            }
+
+            if turns >= self.holding_min_turns && turns > 0 {
+                let pattern = &points[start..=last_turn];
+                let count = pattern.len() as f64;
+                events.push(Holding {
+                    start: EventPosition::new(points[start].0, entry),
+                    end: EventPosition::new(points[last_turn].0, points[last_turn].1),
+                    turns,
+                    center_lat: pattern.iter().map(|p| p.1.lat).sum::<f64>() / count,
+                    center_lon: pattern.iter().map(|p| p.1.lon).sum::<f64>() / count,
+                });
+                start = last_turn + 1;
+            } else {
+                start += 1;
+            }
+        }
+        events
+    }
+
+    /// Checks whether the final landing is away from the destination.
+    /// # Arguments
+    ///   * `flight` - Flight whose tracks are scanned
+    ///   * `destination` - Planned destination airport
+    /// # Returns
+    ///   A `Diversion` when the flight landed more than `diversion_distance_nm`
+    ///   away, `None` when it landed at the destination or the tracks end airborne
+    pub fn diversion(&self, flight: &Flight, destination: &Airport) -> Option<Diversion> {
+        let points = ordered_points(flight);
+        let (_, last) = points.last()?;
+        if !last.on_ground() {
+            return None;
+        }
+
+        // First ground point after the last airborne one
+        let airborne = points.iter().rposition(|(_, t)| !t.on_ground())?;
+        let (time, landing) = points[airborne + 1];
+        let distance_nm =
+            geo::haversine_nm(landing.lat, landing.lon, destination.lat, destination.lon);
+
+        (distance_nm > self.diversion_distance_nm).then(|| Diversion {
+            planned: destination.icao.clone(),
+            landing: EventPosition::new(time, landing),
+            distance_nm,
+        })
+    }
+
+    /// Runs every detector.
+    /// # Arguments
+    ///   * `flight` - Flight whose tracks are scanned
+    ///   * `destination` - Planned destination airport
+    /// # Returns
+    ///   Every `FlightEvent` ordered by start time
+    pub fn detect(&self, flight: &Flight, destination: &Airport) -> Vec<FlightEvent> {
+        let mut events: Vec<FlightEvent> = self
+            .go_arounds(flight, destination)
+            .into_iter()
+            .map(FlightEvent::GoAround)
+            .chain(self.holdings(flight).into_iter().map(FlightEvent::Holding))
+            .chain(
+                self.diversion(flight, destination)
+                    .map(FlightEvent::Diversion),
+            )
+            .collect();
+        events.sort_by_key(FlightEvent::timestamp);
+        events
+    }
+}
+
+impl Flight {
+    /// Finds go-arounds, holding patterns and diversions with the default
+    /// `EventDetector`.
+    /// # Arguments
+    ///   * `destination` - Planned destination airport
+    /// # Returns
+    ///   Every `FlightEvent` ordered by start time
+    pub fn detect_events(&self, destination: &Airport) -> Vec<FlightEvent> {
+        EventDetector::default().detect(self, destination)
+    }
+}
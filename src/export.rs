@@ -0,0 +1,160 @@
+/// Export helpers turning `flight-tracks` API Endpoint resultant data into map formats
+use crate::client::{Flight, Track};
+use chrono::SecondsFormat;
+use serde_json::{json, Value};
+use std::fmt::Write;
+
+/// Conversion factor from API altitude (feet) to map altitude (meters)
+const FEET_TO_METERS: f64 = 0.3048;
+
+/// Build a GeoJSON `FeatureCollection` from flight tracks.
+///
+/// Each flight becomes a `LineString` feature carrying per-point timestamps
+/// (`coordTimes`), followed by one `Point` feature per track with the full
+/// track data as properties.
+/// # Arguments
+///   * `flights` - Flights returned from the `flight-tracks` endpoint
+/// # Returns
+///   A GeoJSON `Value`
+pub fn flights_to_geojson(flights: &[Flight]) -> Value {
+    let mut features: Vec<Value> = Vec::new();
+
+    for flight in flights.iter() {
+        let coordinates: Vec<Value> = flight.tracks.iter().map(coordinate).collect();
+        let times: Vec<String> = flight.tracks.iter().map(iso_timestamp).collect();
+
+        features.push(json!({
+            "type": "Feature",
+            "geometry": { "type": "LineString", "coordinates": coordinates },
+            "properties": {
+                "fr24_id": flight.id,
+                "callsign": flight.tracks.first().map(|t| t.callsign.as_str()),
+                "coordTimes": times,
+            },
+        }));
+
+        for track in flight.tracks.iter() {
+            features.push(json!({
+                "type": "Feature",
+                "geometry": { "type": "Point", "coordinates": coordinate(track) },
+                "properties": {
+                    "fr24_id": flight.id,
+                    "timestamp": iso_timestamp(track),
+                    "alt": track.alt,
+                    "gspeed": track.gspeed,
+                    "vspeed": track.vspeed,
+                    "track": track.track,
+                    "squawk": track.squawk,
+                    "callsign": track.callsign,
+                    "source": track.source,
+                },
+            }));
+        }
+    }
+
+    json!({ "type": "FeatureCollection", "features": features })
+}
+
+/// Build a KML document from flight tracks.
+///
+/// Each flight is written as a `gx:Track` with absolute, extruded altitudes and
+/// a `when` per point so Google Earth can play the flight back over time.
+/// # Arguments
+///   * `flights` - Flights returned from the `flight-tracks` endpoint
+/// # Returns
+///   KML document as a `String`
+pub fn flights_to_kml(flights: &[Flight]) -> String {
+    let mut kml = String::new();
+    kml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    kml.push_str("<kml xmlns=\"http://www.opengis.net/kml/2.2\" xmlns:gx=\"http://www.google.com/kml/ext/2.2\">\n");
+    kml.push_str("<Document>\n");
+    kml.push_str("<Style id=\"flight\"><LineStyle><color>ff0080ff</color><width>2</width></LineStyle><PolyStyle><color>400080ff</color></PolyStyle></Style>\n");
+
+    for flight in flights.iter() {
+        let _ = writeln!(kml, "<Placemark>");
+        let _ = writeln!(kml, "<name>{}</name>", escape_xml(&flight_name(flight)));
+        let _ = writeln!(kml, "<styleUrl>#flight</styleUrl>");
+        let _ = writeln!(kml, "<gx:Track>");
+        let _ = writeln!(kml, "<altitudeMode>absolute</altitudeMode>");
+        let _ = writeln!(kml, "<extrude>1</extrude>");
+        for track in flight.tracks.iter() {
+            let _ = writeln!(kml, "<when>{}</when>", escape_xml(&iso_timestamp(track)));
+        }
+        for track in flight.tracks.iter() {
+            let _ = writeln!(
+                kml,
+                "<gx:coord>{} {} {}</gx:coord>",
+                track.lon,
+                track.lat,
+                track.alt as f64 * FEET_TO_METERS
+            );
+        }
+        let _ = writeln!(kml, "</gx:Track>");
+        let _ = writeln!(kml, "</Placemark>");
+    }
+
+    kml.push_str("</Document>\n</kml>\n");
+    kml
+}
+
+/// Build a GPX 1.1 document with one track per flight.
+/// # Arguments
+///   * `flights` - Flights returned from the `flight-tracks` endpoint
+/// # Returns
+///   GPX document as a `String`
+pub fn flights_to_gpx(flights: &[Flight]) -> String {
+    let mut gpx = String::new();
+    gpx.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    gpx.push_str("<gpx version=\"1.1\" creator=\"flightradar24-api\" xmlns=\"http://www.topografix.com/GPX/1/1\">\n");
+
+    for flight in flights.iter() {
+        let _ = writeln!(gpx, "<trk>");
+        let _ = writeln!(gpx, "<name>{}</name>", escape_xml(&flight_name(flight)));
+        let _ = writeln!(gpx, "<trkseg>");
+        for track in flight.tracks.iter() {
+            let _ = writeln!(
+                gpx,
+                "<trkpt lat=\"{}\" lon=\"{}\"><ele>{}</ele><time>{}</time></trkpt>",
+                track.lat,
+                track.lon,
+                track.alt as f64 * FEET_TO_METERS,
+                escape_xml(&iso_timestamp(track))
+            );
+        }
+        let _ = writeln!(gpx, "</trkseg>");
+        let _ = writeln!(gpx, "</trk>");
+    }
+
+    gpx.push_str("</gpx>\n");
+    gpx
+}
+
+/// GeoJSON position (`[lon, lat, alt_m]`) for a track
+fn coordinate(track: &Track) -> Value {
+    json!([track.lon, track.lat, track.alt as f64 * FEET_TO_METERS])
+}
+
+/// Normalized RFC 3339 timestamp, falling back to the raw API value
+fn iso_timestamp(track: &Track) -> String {
+    match track.parsed_timestamp() {
+        Ok(time) => time.to_rfc3339_opts(SecondsFormat::Secs, true),
+        Err(_) => track.timestamp.to_string(),
+    }
+}
+
+/// Display name of a flight, preferring the callsign over the fr24_id
+fn flight_name(flight: &Flight) -> String {
+    match flight.tracks.iter().find(|t| !t.callsign.is_empty()) {
+        Some(track) => format!("{} ({})", track.callsign, flight.id),
+        None => flight.id.to_string(),
+    }
+}
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
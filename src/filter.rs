@@ -0,0 +1,416 @@
+/// Typed and validated filters for `FullLiveFlightQuery`
+use crate::client::{FullLiveFlightData, FullLiveFlightResponse};
+use crate::error::FlightRadarError;
+use serde::Deserialize;
+use std::fmt;
+use std::str::FromStr;
+
+/// Airport or country code accepted by the `airports` and `routes` filters
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+#[serde(try_from = "String")]
+pub enum AirportCode {
+    /// Three letter IATA airport code, e.g. `ARN`
+    Iata(String),
+    /// Four character ICAO airport code, e.g. `ESSA`
+    Icao(String),
+    /// ISO 3166-1 alpha-2 country code, e.g. `SE`
+    Country(String),
+}
+
+impl AirportCode {
+    /// Code as sent to the API, always upper case
+    pub fn as_str(&self) -> &str {
+        match self {
+            AirportCode::Iata(code) | AirportCode::Icao(code) | AirportCode::Country(code) => code,
+        }
+    }
+}
+
+impl FromStr for AirportCode {
+    type Err = FlightRadarError;
+
+    /// Parses a code, telling the forms apart by length.
+    /// # Arguments
+    ///   * `s` - Two letter country, three letter IATA or four character ICAO code
+    /// # Returns
+    ///   An `AirportCode` on success or a `FlightRadarError::Parameter` on failure.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let code = s.trim().to_ascii_uppercase();
+        let letters = code.chars().all(|c| c.is_ascii_uppercase());
+
+        match code.len() {
+            2 if letters => Ok(AirportCode::Country(code)),
+            3 if letters => Ok(AirportCode::Iata(code)),
+            // ICAO location indicators start with a letter, smaller fields use digits after it
+            4 if code.starts_with(|c: char| c.is_ascii_uppercase())
+                && code
+                    .chars()
+                    .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit()) =>
+            {
+                Ok(AirportCode::Icao(code))
+            }
+            _ => Err(FlightRadarError::Parameter(format!("Airport Code: {}", s))),
+        }
+    }
+}
+
+impl TryFrom<String> for AirportCode {
+    type Error = FlightRadarError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl fmt::Display for AirportCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Which movements an airport filter matches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum AirportDirection {
+    /// Flights arriving at the airport
+    Inbound,
+    /// Flights departing from the airport
+    Outbound,
+    /// Flights arriving at or departing from the airport
+    #[default]
+    Both,
+}
+
+impl AirportDirection {
+    /// Prefix used by the API
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AirportDirection::Inbound => "inbound",
+            AirportDirection::Outbound => "outbound",
+            AirportDirection::Both => "both",
+        }
+    }
+}
+
+/// Entry of the `airports` filter, sent as `direction:CODE`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+#[serde(try_from = "String")]
+pub struct AirportFilter {
+    pub direction: AirportDirection,
+    pub code: AirportCode,
+}
+
+impl AirportFilter {
+    /// Flights arriving at or departing from an airport
+    /// # Arguments
+    ///   * `code` - Country, IATA or ICAO code
+    /// # Returns
+    ///   An `AirportFilter` on success or a `FlightRadarError::Parameter` on failure.
+    pub fn both(code: &str) -> Result<Self, FlightRadarError> {
+        Self::with_direction(AirportDirection::Both, code)
+    }
+
+    /// Flights arriving at an airport
+    pub fn inbound(code: &str) -> Result<Self, FlightRadarError> {
+        Self::with_direction(AirportDirection::Inbound, code)
+    }
+
+    /// Flights departing from an airport
+    pub fn outbound(code: &str) -> Result<Self, FlightRadarError> {
+        Self::with_direction(AirportDirection::Outbound, code)
+    }
+
+    fn with_direction(direction: AirportDirection, code: &str) -> Result<Self, FlightRadarError> {
+        Ok(AirportFilter {
+            direction,
+            code: code.parse()?,
+        })
+    }
+}
+
+impl FromStr for AirportFilter {
+    type Err = FlightRadarError;
+
+    /// Parses `CODE` or `direction:CODE`, no prefix matches both directions.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (direction, code) = match s.trim().split_once(':') {
+            None => (AirportDirection::Both, s),
+            Some((prefix, code)) => {
+                let direction = match prefix.to_ascii_lowercase().as_str() {
+                    "inbound" => AirportDirection::Inbound,
+                    "outbound" => AirportDirection::Outbound,
+                    "both" => AirportDirection::Both,
+                    _ => {
+                        return Err(FlightRadarError::Parameter(format!(
+                            "Airport Direction: {}",
+                            s
+                        )))
+                    }
+                };
+                (direction, code)
+            }
+        };
+        Self::with_direction(direction, code)
+    }
+}
+
+impl TryFrom<String> for AirportFilter {
+    type Error = FlightRadarError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl fmt::Display for AirportFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.direction.as_str(), self.code)
+    }
+}
+
+/// Entry of the `routes` filter, sent as `FROM-TO`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+#[serde(try_from = "String")]
+pub struct RouteFilter {
+    pub from: AirportCode,
+    pub to: AirportCode,
+}
+
+impl RouteFilter {
+    /// Flights between two airports or countries
+    /// # Arguments
+    ///   * `from`, `to` - Country, IATA or ICAO codes
+    /// # Returns
+    ///   A `RouteFilter` on success or a `FlightRadarError::Parameter` on failure.
+    pub fn new(from: &str, to: &str) -> Result<Self, FlightRadarError> {
+        Ok(RouteFilter {
+            from: from.parse()?,
+            to: to.parse()?,
+        })
+    }
+}
+
+impl FromStr for RouteFilter {
+    type Err = FlightRadarError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().split_once('-') {
+            Some((from, to)) => Self::new(from, to),
+            None => Err(FlightRadarError::Parameter(format!("Route: {}", s))),
+        }
+    }
+}
+
+impl TryFrom<String> for RouteFilter {
+    type Error = FlightRadarError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl fmt::Display for RouteFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.from, self.to)
+    }
+}
+
+/// Named group of ICAO type designators
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct AircraftFamily {
+    pub name: &'static str,
+    pub types: &'static [&'static str],
+}
+
+/// Families usable as `family:NAME` in an `AircraftTypeFilter`
+pub const AIRCRAFT_FAMILIES: &[AircraftFamily] = &[
+    AircraftFamily {
+        name: "A220",
+        types: &["BCS1", "BCS3"],
+    },
+    AircraftFamily {
+        name: "A320",
+        types: &["A318", "A319", "A320", "A321", "A19N", "A20N", "A21N"],
+    },
+    AircraftFamily {
+        name: "A330",
+        types: &["A332", "A333", "A337", "A338", "A339"],
+    },
+    AircraftFamily {
+        name: "A350",
+        types: &["A359", "A35K"],
+    },
+    AircraftFamily {
+        name: "A380",
+        types: &["A388"],
+    },
+    AircraftFamily {
+        name: "737",
+        types: &[
+            "B731", "B732", "B733", "B734", "B735", "B736", "B737", "B738", "B739", "B37M", "B38M",
+            "B39M", "B3XM",
+        ],
+    },
+    AircraftFamily {
+        name: "747",
+        types: &["B741", "B742", "B743", "B744", "B748", "B74R", "B74S"],
+    },
+    AircraftFamily {
+        name: "757",
+        types: &["B752", "B753"],
+    },
+    AircraftFamily {
+        name: "767",
+        types: &["B762", "B763", "B764"],
+    },
+    AircraftFamily {
+        name: "777",
+        types: &["B772", "B773", "B77L", "B77W", "B778", "B779"],
+    },
+    AircraftFamily {
+        name: "787",
+        types: &["B788", "B789", "B78X"],
+    },
+    AircraftFamily {
+        name: "ATR",
+        types: &[
+            "AT43", "AT44", "AT45", "AT46", "AT72", "AT73", "AT75", "AT76",
+        ],
+    },
+    AircraftFamily {
+        name: "CRJ",
+        types: &["CRJ1", "CRJ2", "CRJ7", "CRJ9", "CRJX"],
+    },
+    AircraftFamily {
+        name: "DASH8",
+        types: &["DH8A", "DH8B", "DH8C", "DH8D"],
+    },
+    AircraftFamily {
+        name: "EJET",
+        types: &["E170", "E75L", "E75S", "E190", "E195", "E290", "E295"],
+    },
+];
+
+/// Entry of the `aircraft` filter
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+#[serde(try_from = "String")]
+pub enum AircraftTypeFilter {
+    /// Single ICAO type designator, e.g. `B38M`
+    Exact(String),
+    /// Designators starting with a prefix, written `A32*`
+    Prefix(String),
+    /// Designators ending with a suffix, written `*33`
+    Suffix(String),
+    /// Designators starting and ending with the given parts, written `A3*3`
+    Infix(String, String),
+    /// Every designator of a family, written `family:A320`
+    Family(&'static AircraftFamily),
+}
+
+impl AircraftTypeFilter {
+    /// Whether an aircraft type matches, ignoring case
+    /// # Arguments
+    ///   * `type_field` - ICAO type designator such as `FullLiveFlightData.type_field`
+    pub fn matches(&self, type_field: &str) -> bool {
+        let type_field = type_field.trim().to_ascii_uppercase();
+        match self {
+            AircraftTypeFilter::Exact(code) => type_field == *code,
+            AircraftTypeFilter::Prefix(prefix) => type_field.starts_with(prefix.as_str()),
+            AircraftTypeFilter::Suffix(suffix) => type_field.ends_with(suffix.as_str()),
+            AircraftTypeFilter::Infix(start, end) => {
+                type_field.len() >= start.len() + end.len()
+                    && type_field.starts_with(start.as_str())
+                    && type_field.ends_with(end.as_str())
+            }
+            AircraftTypeFilter::Family(family) => family.types.contains(&type_field.as_str()),
+        }
+    }
+
+    /// Values sent to the API, families expand to their designators
+    pub fn api_values(&self) -> Vec<String> {
+        match self {
+            AircraftTypeFilter::Family(family) => {
+                family.types.iter().map(|t| t.to_string()).collect()
+            }
+            _ => vec![self.to_string()],
+        }
+    }
+}
+
+/// Whether an aircraft type matches any of the filters, an empty list matches everything
+/// # Arguments
+///   * `filters` - Filters to test
+///   * `type_field` - ICAO type designator such as `FullLiveFlightData.type_field`
+pub fn matches_aircraft_type(filters: &[AircraftTypeFilter], type_field: &str) -> bool {
+    filters.is_empty() || filters.iter().any(|f| f.matches(type_field))
+}
+
+impl FromStr for AircraftTypeFilter {
+    type Err = FlightRadarError;
+
+    /// Parses a designator with at most one `*` wildcard, or `family:NAME`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = s.trim().to_ascii_uppercase();
+        let invalid = || FlightRadarError::Parameter(format!("Aircraft: {}", s));
+
+        if let Some(name) = value.strip_prefix("FAMILY:") {
+            return AIRCRAFT_FAMILIES
+                .iter()
+                .find(|f| f.name == name)
+                .map(AircraftTypeFilter::Family)
+                .ok_or_else(invalid);
+        }
+
+        let designator: String = value.chars().filter(|c| *c != '*').collect();
+        if designator.is_empty()
+            || designator.len() > 4
+            || !designator.chars().all(|c| c.is_ascii_alphanumeric())
+        {
+            return Err(invalid());
+        }
+
+        match value.split('*').collect::<Vec<&str>>().as_slice() {
+            [exact] if exact.len() >= 2 => Ok(AircraftTypeFilter::Exact(exact.to_string())),
+            ["", suffix] => Ok(AircraftTypeFilter::Suffix(suffix.to_string())),
+            [prefix, ""] => Ok(AircraftTypeFilter::Prefix(prefix.to_string())),
+            [start, end] => Ok(AircraftTypeFilter::Infix(
+                start.to_string(),
+                end.to_string(),
+            )),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl TryFrom<String> for AircraftTypeFilter {
+    type Error = FlightRadarError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl fmt::Display for AircraftTypeFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AircraftTypeFilter::Exact(code) => write!(f, "{}", code),
+            AircraftTypeFilter::Prefix(prefix) => write!(f, "{}*", prefix),
+            AircraftTypeFilter::Suffix(suffix) => write!(f, "*{}", suffix),
+            AircraftTypeFilter::Infix(start, end) => write!(f, "{}*{}", start, end),
+            AircraftTypeFilter::Family(family) => write!(f, "family:{}", family.name),
+        }
+    }
+}
+
+impl FullLiveFlightResponse {
+    /// Aircraft matching any of the type filters, for narrowing cached or archived snapshots
+    /// # Arguments
+    ///   * `filters` - Type filters, an empty list keeps every aircraft
+    /// # Returns
+    ///   References to the matching `FullLiveFlightData`
+    pub fn filter_aircraft(&self, filters: &[AircraftTypeFilter]) -> Vec<&FullLiveFlightData> {
+        self.data
+            .iter()
+            .filter(|f| matches_aircraft_type(filters, &f.type_field))
+            .collect()
+    }
+}
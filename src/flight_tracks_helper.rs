@@ -1,124 +1,248 @@
 /// Helper functions for the `flight-tracks` API Endpoint resultant data
-use crate::client::Flight;
+use crate::client::{Flight, Track};
+use crate::error::FlightRadarError;
 use chrono::{DateTime, Utc};
 
-pub fn get_timestamps_from_flight(flights: &[Flight]) -> Vec<String> {
-    let mut return_val: Vec<String> = Vec::new();
+/// Values of one track field for a single flight
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlightColumn<'a, T> {
+    pub fr24_id: &'a str,
+    pub values: Vec<T>,
+}
 
-    for flight in flights.iter() {
-        for data in flight.tracks.iter() {
-            let now_parsed: DateTime<Utc> = data.timestamp.to_string().parse().unwrap();
-            return_val.push(now_parsed.to_string());
-        }
+/// Every track field of a flight as parallel vectors
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TrackColumns {
+    pub fr24_id: String,
+    pub timestamp: Vec<String>,
+    pub lat: Vec<f64>,
+    pub lon: Vec<f64>,
+    pub alt: Vec<u32>,
+    pub gspeed: Vec<u32>,
+    pub vspeed: Vec<i32>,
+    pub track: Vec<u32>,
+    pub squawk: Vec<String>,
+    pub callsign: Vec<String>,
+    pub source: Vec<String>,
+}
+
+impl TrackColumns {
+    /// Number of track points
+    pub fn len(&self) -> usize {
+        self.timestamp.len()
     }
 
-    return_val
+    /// Whether the flight has no track points
+    pub fn is_empty(&self) -> bool {
+        self.timestamp.is_empty()
+    }
 }
 
-pub fn get_gspeed_from_flight(flights: &[Flight]) -> Vec<u32> {
-    let mut return_val: Vec<u32> = Vec::new();
-
-    for flight in flights.iter() {
-        for data in flight.tracks.iter() {
-            return_val.push(data.gspeed);
+impl From<&Flight> for TrackColumns {
+    fn from(flight: &Flight) -> Self {
+        let mut columns = TrackColumns {
+            fr24_id: flight.id.clone(),
+            ..TrackColumns::default()
+        };
+        for track in &flight.tracks {
+            columns.timestamp.push(track.timestamp.clone());
+            columns.lat.push(track.lat);
+            columns.lon.push(track.lon);
+            columns.alt.push(track.alt);
+            columns.gspeed.push(track.gspeed);
+            columns.vspeed.push(track.vspeed);
+            columns.track.push(track.track);
+            columns.squawk.push(track.squawk.clone());
+            columns.callsign.push(track.callsign.clone());
+            columns.source.push(track.source.clone());
         }
+        columns
     }
+}
 
-    return_val
+impl Flight {
+    /// Track fields as parallel vectors
+    pub fn columns(&self) -> TrackColumns {
+        TrackColumns::from(self)
+    }
 }
 
-pub fn get_lat_from_flight(flights: &[Flight]) -> Vec<f64> {
-    let mut return_val: Vec<f64> = Vec::new();
+/// Iterator over the track points of several flights, see `FlightTracks::points`
+pub struct TrackPoints<'a> {
+    flights: std::slice::Iter<'a, Flight>,
+    current: Option<(&'a str, std::slice::Iter<'a, Track>)>,
+}
 
-    for flight in flights.iter() {
-        for data in flight.tracks.iter() {
-            return_val.push(data.lat);
+impl<'a> Iterator for TrackPoints<'a> {
+    type Item = (&'a str, &'a Track);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((id, tracks)) = &mut self.current {
+                if let Some(track) = tracks.next() {
+                    return Some((id, track));
+                }
+            }
+            let flight = self.flights.next()?;
+            self.current = Some((flight.id.as_str(), flight.tracks.iter()));
         }
     }
-
-    return_val
 }
 
-pub fn get_lon_from_flight(flights: &[Flight]) -> Vec<f64> {
-    let mut return_val: Vec<f64> = Vec::new();
+/// Per-flight access to the tracks of a `flight-tracks` response
+pub trait FlightTracks {
+    /// Every track point with the `fr24_id` of its flight, flight by flight
+    fn points(&self) -> TrackPoints<'_>;
+
+    /// Values of a track field, grouped by flight
+    /// # Arguments
+    ///   * `field` - Extracts the value from a track point
+    /// # Returns
+    ///   One `FlightColumn` per flight, in input order
+    fn column<'a, T, F: Fn(&'a Track) -> T>(&'a self, field: F) -> Vec<FlightColumn<'a, T>>;
+
+    /// Every track field of each flight as parallel vectors
+    fn columns(&self) -> Vec<TrackColumns>;
+
+    /// Parsed timestamps, grouped by flight
+    /// # Returns
+    ///   The columns on success or a `FlightRadarError` for the first unreadable timestamp.
+    fn timestamps(&self) -> Result<Vec<FlightColumn<'_, DateTime<Utc>>>, FlightRadarError> {
+        self.column(Track::parsed_timestamp)
+            .into_iter()
+            .map(|c| {
+                Ok(FlightColumn {
+                    fr24_id: c.fr24_id,
+                    values: c.values.into_iter().collect::<Result<_, _>>()?,
+                })
+            })
+            .collect()
+    }
 
-    for flight in flights.iter() {
-        for data in flight.tracks.iter() {
-            return_val.push(data.lon);
-        }
+    /// Latitudes, grouped by flight
+    fn lats(&self) -> Vec<FlightColumn<'_, f64>> {
+        self.column(|t| t.lat)
     }
 
-    return_val
-}
+    /// Longitudes, grouped by flight
+    fn lons(&self) -> Vec<FlightColumn<'_, f64>> {
+        self.column(|t| t.lon)
+    }
 
-pub fn get_alt_from_flight(flights: &[Flight]) -> Vec<u32> {
-    let mut return_val: Vec<u32> = Vec::new();
+    /// Altitudes, grouped by flight
+    fn alts(&self) -> Vec<FlightColumn<'_, u32>> {
+        self.column(|t| t.alt)
+    }
 
-    for flight in flights.iter() {
-        for data in flight.tracks.iter() {
-            return_val.push(data.alt);
-        }
+    /// Ground speeds, grouped by flight
+    fn gspeeds(&self) -> Vec<FlightColumn<'_, u32>> {
+        self.column(|t| t.gspeed)
     }
 
-    return_val
-}
+    /// Vertical speeds, grouped by flight
+    fn vspeeds(&self) -> Vec<FlightColumn<'_, i32>> {
+        self.column(|t| t.vspeed)
+    }
 
-pub fn get_vspeed_from_flight(flights: &[Flight]) -> Vec<u32> {
-    let mut return_val: Vec<u32> = Vec::new();
+    /// Ground tracks (headings), grouped by flight
+    fn headings(&self) -> Vec<FlightColumn<'_, u32>> {
+        self.column(|t| t.track)
+    }
 
-    for flight in flights.iter() {
-        for data in flight.tracks.iter() {
-            return_val.push(data.vspeed);
-        }
+    /// Squawk codes, grouped by flight
+    fn squawks(&self) -> Vec<FlightColumn<'_, &str>> {
+        self.column(|t| t.squawk.as_str())
     }
 
-    return_val
-}
+    /// Callsigns, grouped by flight
+    fn callsigns(&self) -> Vec<FlightColumn<'_, &str>> {
+        self.column(|t| t.callsign.as_str())
+    }
 
-pub fn get_track_from_flight(flights: &[Flight]) -> Vec<u32> {
-    let mut return_val: Vec<u32> = Vec::new();
+    /// Position sources, grouped by flight
+    fn sources(&self) -> Vec<FlightColumn<'_, &str>> {
+        self.column(|t| t.source.as_str())
+    }
+}
 
-    for flight in flights.iter() {
-        for data in flight.tracks.iter() {
-            return_val.push(data.track);
+impl FlightTracks for [Flight] {
+    fn points(&self) -> TrackPoints<'_> {
+        TrackPoints {
+            flights: self.iter(),
+            current: None,
         }
     }
 
-    return_val
+    fn column<'a, T, F: Fn(&'a Track) -> T>(&'a self, field: F) -> Vec<FlightColumn<'a, T>> {
+        self.iter()
+            .map(|flight| FlightColumn {
+                fr24_id: flight.id.as_str(),
+                values: flight.tracks.iter().map(&field).collect(),
+            })
+            .collect()
+    }
+
+    fn columns(&self) -> Vec<TrackColumns> {
+        self.iter().map(TrackColumns::from).collect()
+    }
 }
 
-pub fn get_squack_from_flight(flights: &[Flight]) -> Vec<String> {
-    let mut return_val: Vec<String> = Vec::new();
+pub fn get_timestamps_from_flight(flights: &[Flight]) -> Vec<String> {
+    flights
+        .points()
+        .map(|(_, t)| {
+            let now_parsed: DateTime<Utc> = t.timestamp.parse().unwrap();
+            now_parsed.to_string()
+        })
+        .collect()
+}
 
-    for flight in flights.iter() {
-        for data in flight.tracks.iter() {
-            return_val.push(data.squawk.to_string());
-        }
-    }
+pub fn get_gspeed_from_flight(flights: &[Flight]) -> Vec<u32> {
+    flights.points().map(|(_, t)| t.gspeed).collect()
+}
 
-    return_val
+pub fn get_lat_from_flight(flights: &[Flight]) -> Vec<f64> {
+    flights.points().map(|(_, t)| t.lat).collect()
 }
 
-pub fn get_callsign_from_flight(flights: &[Flight]) -> Vec<String> {
-    let mut return_val: Vec<String> = Vec::new();
+pub fn get_lon_from_flight(flights: &[Flight]) -> Vec<f64> {
+    flights.points().map(|(_, t)| t.lon).collect()
+}
 
-    for flight in flights.iter() {
-        for data in flight.tracks.iter() {
-            return_val.push(data.callsign.to_string());
-        }
-    }
+pub fn get_alt_from_flight(flights: &[Flight]) -> Vec<u32> {
+    flights.points().map(|(_, t)| t.alt).collect()
+}
 
-    return_val
+pub fn get_vspeed_from_flight(flights: &[Flight]) -> Vec<i32> {
+    flights.points().map(|(_, t)| t.vspeed).collect()
 }
 
-pub fn get_source_from_flight(flights: &[Flight]) -> Vec<String> {
-    let mut return_val: Vec<String> = Vec::new();
+pub fn get_track_from_flight(flights: &[Flight]) -> Vec<u32> {
+    flights.points().map(|(_, t)| t.track).collect()
+}
 
-    for flight in flights.iter() {
-        for data in flight.tracks.iter() {
-            return_val.push(data.source.to_string());
-        }
-    }
+pub fn get_squawk_from_flight(flights: &[Flight]) -> Vec<String> {
+    flights
+        .points()
+        .map(|(_, t)| t.squawk.to_string())
+        .collect()
+}
 
-    return_val
+/// Misspelled name of `get_squawk_from_flight`, kept for existing callers
+pub fn get_squack_from_flight(flights: &[Flight]) -> Vec<String> {
+    get_squawk_from_flight(flights)
+}
+
+pub fn get_callsign_from_flight(flights: &[Flight]) -> Vec<String> {
+    flights
+        .points()
+        .map(|(_, t)| t.callsign.to_string())
+        .collect()
+}
+
+pub fn get_source_from_flight(flights: &[Flight]) -> Vec<String> {
+    flights
+        .points()
+        .map(|(_, t)| t.source.to_string())
+        .collect()
 }
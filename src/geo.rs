@@ -0,0 +1,159 @@
+/// Mean earth radius in nautical miles
+pub const EARTH_RADIUS_NM: f64 = 3440.065;
+/// Ground speed (knots) below which an aircraft is treated as on the ground
+pub const GROUND_SPEED_KT: u32 = 50;
+
+/// Whether a reported state is on the ground
+/// # Arguments
+///   * `alt` - Reported altitude in feet, `0` on the ground
+///   * `gspeed` - Ground speed in knots
+/// # Returns
+///   `true` at zero altitude or below `GROUND_SPEED_KT`
+pub fn on_ground(alt: u32, gspeed: u32) -> bool {
+    alt == 0 || gspeed < GROUND_SPEED_KT
+}
+
+/// Great-circle distance between two positions
+/// # Arguments
+///   * `lat1`, `lon1` - First position in degrees
+///   * `lat2`, `lon2` - Second position in degrees
+/// # Returns
+///   Distance in nautical miles
+pub fn haversine_nm(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let d_phi = (lat2 - lat1).to_radians();
+    let d_lambda = (lon2 - lon1).to_radians();
+
+    let a = (d_phi / 2.0).sin().powi(2) + phi1.cos() * phi2.cos() * (d_lambda / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_NM * a.sqrt().atan2((1.0 - a).sqrt())
+}
+
+/// Initial great-circle bearing from the first position towards the second
+/// # Returns
+///   Bearing in degrees within `[0, 360)`
+pub fn initial_bearing(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let d_lambda = (lon2 - lon1).to_radians();
+
+    let y = d_lambda.sin() * phi2.cos();
+    let x = phi1.cos() * phi2.sin() - phi1.sin() * phi2.cos() * d_lambda.cos();
+    normalize_heading(y.atan2(x).to_degrees())
+}
+
+/// Intermediate point along the great circle between two positions
+/// # Arguments
+///   * `fraction` - `0.0` returns the first position, `1.0` the second
+/// # Returns
+///   `(lat, lon)` in degrees
+pub fn interpolate_great_circle(
+    lat1: f64,
+    lon1: f64,
+    lat2: f64,
+    lon2: f64,
+    fraction: f64,
+) -> (f64, f64) {
+    let delta = haversine_nm(lat1, lon1, lat2, lon2) / EARTH_RADIUS_NM;
+    if delta < 1e-12 {
+        return (lat1, lon1);
+    }
+
+    let (phi1, lambda1) = (lat1.to_radians(), lon1.to_radians());
+    let (phi2, lambda2) = (lat2.to_radians(), lon2.to_radians());
+    let a = ((1.0 - fraction) * delta).sin() / delta.sin();
+    let b = (fraction * delta).sin() / delta.sin();
+
+    let x = a * phi1.cos() * lambda1.cos() + b * phi2.cos() * lambda2.cos();
+    let y = a * phi1.cos() * lambda1.sin() + b * phi2.cos() * lambda2.sin();
+    let z = a * phi1.sin() + b * phi2.sin();
+
+    (
+        z.atan2((x * x + y * y).sqrt()).to_degrees(),
+        y.atan2(x).to_degrees(),
+    )
+}
+
+/// Interpolate between two headings along the shortest turn
+/// # Arguments
+///   * `fraction` - `0.0` returns the first heading, `1.0` the second
+/// # Returns
+///   Heading in degrees within `[0, 360)`
+pub fn interpolate_heading(from: f64, to: f64, fraction: f64) -> f64 {
+    normalize_heading(from + heading_difference(from, to) * fraction)
+}
+
+/// Signed shortest turn from one heading to another
+/// # Returns
+///   Difference in degrees within `[-180, 180)`
+pub fn heading_difference(from: f64, to: f64) -> f64 {
+    (to - from + 540.0).rem_euclid(360.0) - 180.0
+}
+
+/// Wrap a heading into `[0, 360)`
+pub fn normalize_heading(heading: f64) -> f64 {
+    heading.rem_euclid(360.0)
+}
+
+/// Earth-centred cartesian coordinates of a position on the mean sphere
+///
+/// The straight-line distance between two such points never exceeds their
+/// great-circle distance, which makes them suitable for spatial indexing.
+/// # Returns
+///   `[x, y, z]` in nautical miles
+pub fn to_cartesian_nm(lat: f64, lon: f64) -> [f64; 3] {
+    let (phi, lambda) = (lat.to_radians(), lon.to_radians());
+    [
+        EARTH_RADIUS_NM * phi.cos() * lambda.cos(),
+        EARTH_RADIUS_NM * phi.cos() * lambda.sin(),
+        EARTH_RADIUS_NM * phi.sin(),
+    ]
+}
+
+/// Offset of the second position from the first on a local flat plane
+///
+/// Accurate over the few tens of nautical miles separation work deals with.
+/// # Returns
+///   `(east, north)` in nautical miles
+pub fn local_offset_nm(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> (f64, f64) {
+    let mean_lat = ((lat1 + lat2) / 2.0).to_radians();
+    let d_lon = heading_difference(0.0, lon2 - lon1);
+    (
+        d_lon.to_radians() * mean_lat.cos() * EARTH_RADIUS_NM,
+        (lat2 - lat1).to_radians() * EARTH_RADIUS_NM,
+    )
+}
+
+/// Distance from a position to the great-circle segment between two others
+/// # Arguments
+///   * `lat`, `lon` - Position to measure from
+///   * `lat1`, `lon1`, `lat2`, `lon2` - Segment end points
+/// # Returns
+///   `(distance, along)` in nautical miles, where `along` is the distance
+///   from the segment start to the closest point on the segment
+pub fn segment_distance_nm(
+    lat: f64,
+    lon: f64,
+    lat1: f64,
+    lon1: f64,
+    lat2: f64,
+    lon2: f64,
+) -> (f64, f64) {
+    let length = haversine_nm(lat1, lon1, lat2, lon2);
+    let from_start = haversine_nm(lat1, lon1, lat, lon);
+    if length < 1e-9 {
+        return (from_start, 0.0);
+    }
+
+    let delta = from_start / EARTH_RADIUS_NM;
+    let angle = (initial_bearing(lat1, lon1, lat, lon) - initial_bearing(lat1, lon1, lat2, lon2))
+        .to_radians();
+    let cross = (delta.sin() * angle.sin()).asin();
+    let along = (delta.cos() / cross.cos()).clamp(-1.0, 1.0).acos() * EARTH_RADIUS_NM;
+
+    if angle.cos() < 0.0 {
+        (from_start, 0.0)
+    } else if along > length {
+        (haversine_nm(lat2, lon2, lat, lon), length)
+    } else {
+        (cross.abs() * EARTH_RADIUS_NM, along)
+    }
+}
@@ -0,0 +1,579 @@
+/// ICAO 24-bit aircraft address decoding
+use crate::client::{FullLiveFlightData, LightLiveFlightData};
+use crate::error::FlightRadarError;
+use std::fmt;
+use std::str::FromStr;
+
+/// Range of addresses allocated to one state by ICAO Annex 10, Volume III
+#[derive(Debug, PartialEq, Eq)]
+pub struct AddressBlock {
+    pub start: u32,
+    pub end: u32,
+    pub country: &'static str,
+    /// Nationality marks used in registrations of that state
+    pub registration_prefixes: &'static [&'static str],
+}
+
+const fn block(
+    start: u32,
+    end: u32,
+    country: &'static str,
+    registration_prefixes: &'static [&'static str],
+) -> AddressBlock {
+    AddressBlock {
+        start,
+        end,
+        country,
+        registration_prefixes,
+    }
+}
+
+/// Address blocks ordered by start address
+pub const ADDRESS_BLOCKS: &[AddressBlock] = &[
+    block(0x004000, 0x0043FF, "Zimbabwe", &["Z"]),
+    block(0x006000, 0x006FFF, "Mozambique", &["C9"]),
+    block(0x008000, 0x00FFFF, "South Africa", &["ZS", "ZT", "ZU"]),
+    block(0x010000, 0x017FFF, "Egypt", &["SU"]),
+    block(0x018000, 0x01FFFF, "Libya", &["5A"]),
+    block(0x020000, 0x027FFF, "Morocco", &["CN"]),
+    block(0x028000, 0x02FFFF, "Tunisia", &["TS"]),
+    block(0x030000, 0x0303FF, "Botswana", &["A2"]),
+    block(0x032000, 0x032FFF, "Burundi", &["9U"]),
+    block(0x034000, 0x034FFF, "Cameroon", &["TJ"]),
+    block(0x035000, 0x0353FF, "Comoros", &["D6"]),
+    block(0x036000, 0x036FFF, "Congo", &["TN"]),
+    block(0x038000, 0x038FFF, "Cote d'Ivoire", &["TU"]),
+    block(0x03E000, 0x03EFFF, "Gabon", &["TR"]),
+    block(0x040000, 0x040FFF, "Ethiopia", &["ET"]),
+    block(0x042000, 0x042FFF, "Equatorial Guinea", &["3C"]),
+    block(0x044000, 0x044FFF, "Ghana", &["9G"]),
+    block(0x046000, 0x046FFF, "Guinea", &["3X"]),
+    block(0x048000, 0x0483FF, "Guinea-Bissau", &["J5"]),
+    block(0x04A000, 0x04A3FF, "Lesotho", &["7P"]),
+    block(0x04C000, 0x04CFFF, "Kenya", &["5Y"]),
+    block(0x050000, 0x050FFF, "Liberia", &["A8"]),
+    block(0x054000, 0x054FFF, "Madagascar", &["5R"]),
+    block(0x058000, 0x058FFF, "Malawi", &["7Q"]),
+    block(0x05A000, 0x05A3FF, "Maldives", &["8Q"]),
+    block(0x05C000, 0x05CFFF, "Mali", &["TZ"]),
+    block(0x05E000, 0x05E3FF, "Mauritania", &["5T"]),
+    block(0x060000, 0x0603FF, "Mauritius", &["3B"]),
+    block(0x062000, 0x062FFF, "Niger", &["5U"]),
+    block(0x064000, 0x064FFF, "Nigeria", &["5N"]),
+    block(0x068000, 0x068FFF, "Uganda", &["5X"]),
+    block(0x06A000, 0x06A3FF, "Qatar", &["A7"]),
+    block(0x06C000, 0x06CFFF, "Central African Republic", &["TL"]),
+    block(0x06E000, 0x06EFFF, "Rwanda", &["9XR"]),
+    block(0x070000, 0x070FFF, "Senegal", &["6V", "6W"]),
+    block(0x074000, 0x0743FF, "Seychelles", &["S7"]),
+    block(0x076000, 0x0763FF, "Sierra Leone", &["9L"]),
+    block(0x078000, 0x078FFF, "Somalia", &["6O"]),
+    block(0x07A000, 0x07A3FF, "Eswatini", &["3D"]),
+    block(0x07C000, 0x07CFFF, "Sudan", &["ST"]),
+    block(0x080000, 0x080FFF, "Tanzania", &["5H"]),
+    block(0x084000, 0x084FFF, "Chad", &["TT"]),
+    block(0x088000, 0x088FFF, "Togo", &["5V"]),
+    block(0x08A000, 0x08AFFF, "Zambia", &["9J"]),
+    block(0x08C000, 0x08CFFF, "DR Congo", &["9S", "9Q"]),
+    block(0x090000, 0x090FFF, "Angola", &["D2"]),
+    block(0x094000, 0x0943FF, "Benin", &["TY"]),
+    block(0x096000, 0x0963FF, "Cape Verde", &["D4"]),
+    block(0x098000, 0x0983FF, "Djibouti", &["J2"]),
+    block(0x09A000, 0x09AFFF, "Gambia", &["C5"]),
+    block(0x09C000, 0x09CFFF, "Burkina Faso", &["XT"]),
+    block(0x09E000, 0x09E3FF, "Sao Tome and Principe", &["S9"]),
+    block(0x0A0000, 0x0A7FFF, "Algeria", &["7T"]),
+    block(0x0A8000, 0x0A8FFF, "Bahamas", &["C6"]),
+    block(0x0AA000, 0x0AA3FF, "Barbados", &["8P"]),
+    block(0x0AB000, 0x0AB3FF, "Belize", &["V3"]),
+    block(0x0AC000, 0x0ACFFF, "Colombia", &["HK"]),
+    block(0x0AE000, 0x0AEFFF, "Costa Rica", &["TI"]),
+    block(0x0B0000, 0x0B0FFF, "Cuba", &["CU"]),
+    block(0x0B2000, 0x0B2FFF, "El Salvador", &["YS"]),
+    block(0x0B4000, 0x0B4FFF, "Guatemala", &["TG"]),
+    block(0x0B6000, 0x0B6FFF, "Guyana", &["8R"]),
+    block(0x0B8000, 0x0B8FFF, "Haiti", &["HH"]),
+    block(0x0BA000, 0x0BAFFF, "Honduras", &["HR"]),
+    block(
+        0x0BC000,
+        0x0BC3FF,
+        "Saint Vincent and the Grenadines",
+        &["J8"],
+    ),
+    block(0x0BE000, 0x0BEFFF, "Jamaica", &["6Y"]),
+    block(0x0C0000, 0x0C0FFF, "Nicaragua", &["YN"]),
+    block(0x0C2000, 0x0C2FFF, "Panama", &["HP"]),
+    block(0x0C4000, 0x0C4FFF, "Dominican Republic", &["HI"]),
+    block(0x0C6000, 0x0C6FFF, "Trinidad and Tobago", &["9Y"]),
+    block(0x0C8000, 0x0C8FFF, "Suriname", &["PZ"]),
+    block(0x0CA000, 0x0CA3FF, "Antigua and Barbuda", &["V2"]),
+    block(0x0CC000, 0x0CC3FF, "Grenada", &["J3"]),
+    block(0x0D0000, 0x0D7FFF, "Mexico", &["XA", "XB", "XC"]),
+    block(0x0D8000, 0x0DFFFF, "Venezuela", &["YV"]),
+    block(0x100000, 0x1FFFFF, "Russia", &["RA", "RF"]),
+    block(0x201000, 0x2013FF, "Namibia", &["V5"]),
+    block(0x202000, 0x2023FF, "Eritrea", &["E3"]),
+    block(0x300000, 0x33FFFF, "Italy", &["I"]),
+    block(0x340000, 0x37FFFF, "Spain", &["EC"]),
+    block(0x380000, 0x3BFFFF, "France", &["F"]),
+    block(0x3C0000, 0x3FFFFF, "Germany", &["D"]),
+    block(
+        0x400000,
+        0x43FFFF,
+        "United Kingdom",
+        &["G", "VP", "VQ", "ZB", "ZH"],
+    ),
+    block(0x440000, 0x447FFF, "Austria", &["OE"]),
+    block(0x448000, 0x44FFFF, "Belgium", &["OO"]),
+    block(0x450000, 0x457FFF, "Bulgaria", &["LZ"]),
+    block(0x458000, 0x45FFFF, "Denmark", &["OY"]),
+    block(0x460000, 0x467FFF, "Finland", &["OH"]),
+    block(0x468000, 0x46FFFF, "Greece", &["SX"]),
+    block(0x470000, 0x477FFF, "Hungary", &["HA"]),
+    block(0x478000, 0x47FFFF, "Norway", &["LN"]),
+    block(0x480000, 0x487FFF, "Netherlands", &["PH"]),
+    block(0x488000, 0x48FFFF, "Poland", &["SP", "SN"]),
+    block(0x490000, 0x497FFF, "Portugal", &["CS", "CR"]),
+    block(0x498000, 0x49FFFF, "Czechia", &["OK"]),
+    block(0x4A0000, 0x4A7FFF, "Romania", &["YR"]),
+    block(0x4A8000, 0x4AFFFF, "Sweden", &["SE"]),
+    block(0x4B0000, 0x4B7FFF, "Switzerland", &["HB"]),
+    block(0x4B8000, 0x4BFFFF, "Turkey", &["TC"]),
+    block(0x4C0000, 0x4C7FFF, "Serbia", &["YU"]),
+    block(0x4C8000, 0x4C83FF, "Cyprus", &["5B"]),
+    block(0x4CA000, 0x4CAFFF, "Ireland", &["EI", "EJ"]),
+    block(0x4CC000, 0x4CCFFF, "Iceland", &["TF"]),
+    block(0x4D0000, 0x4D03FF, "Luxembourg", &["LX"]),
+    block(0x4D2000, 0x4D23FF, "Malta", &["9H"]),
+    block(0x4D4000, 0x4D43FF, "Monaco", &["3A"]),
+    block(0x500000, 0x5003FF, "San Marino", &["T7"]),
+    block(0x501000, 0x5013FF, "Albania", &["ZA"]),
+    block(0x501C00, 0x501FFF, "Croatia", &["9A"]),
+    block(0x502C00, 0x502FFF, "Latvia", &["YL"]),
+    block(0x503C00, 0x503FFF, "Lithuania", &["LY"]),
+    block(0x504C00, 0x504FFF, "Moldova", &["ER"]),
+    block(0x505C00, 0x505FFF, "Slovakia", &["OM"]),
+    block(0x506C00, 0x506FFF, "Slovenia", &["S5"]),
+    block(0x507C00, 0x507FFF, "Uzbekistan", &["UK"]),
+    block(0x508000, 0x50FFFF, "Ukraine", &["UR"]),
+    block(0x510000, 0x5103FF, "Belarus", &["EW"]),
+    block(0x511000, 0x5113FF, "Estonia", &["ES"]),
+    block(0x512000, 0x5123FF, "North Macedonia", &["Z3"]),
+    block(0x513000, 0x5133FF, "Bosnia and Herzegovina", &["E7"]),
+    block(0x514000, 0x5143FF, "Georgia", &["4L"]),
+    block(0x515000, 0x5153FF, "Tajikistan", &["EY"]),
+    block(0x516000, 0x5163FF, "Montenegro", &["4O"]),
+    block(0x600000, 0x6003FF, "Armenia", &["EK"]),
+    block(0x600800, 0x600BFF, "Azerbaijan", &["4K"]),
+    block(0x601000, 0x6013FF, "Kyrgyzstan", &["EX"]),
+    block(0x601800, 0x601BFF, "Turkmenistan", &["EZ"]),
+    block(0x680000, 0x6803FF, "Bhutan", &["A5"]),
+    block(0x681000, 0x6813FF, "Micronesia", &["V6"]),
+    block(0x682000, 0x6823FF, "Mongolia", &["JU"]),
+    block(0x683000, 0x6833FF, "Kazakhstan", &["UP"]),
+    block(0x684000, 0x6843FF, "Palau", &["T8"]),
+    block(0x700000, 0x700FFF, "Afghanistan", &["YA"]),
+    block(0x702000, 0x702FFF, "Bangladesh", &["S2"]),
+    block(0x704000, 0x704FFF, "Myanmar", &["XY"]),
+    block(0x706000, 0x706FFF, "Kuwait", &["9K"]),
+    block(0x708000, 0x708FFF, "Laos", &["RDPL"]),
+    block(0x70A000, 0x70AFFF, "Nepal", &["9N"]),
+    block(0x70C000, 0x70C3FF, "Oman", &["A4O"]),
+    block(0x70E000, 0x70EFFF, "Cambodia", &["XU"]),
+    block(0x710000, 0x717FFF, "Saudi Arabia", &["HZ"]),
+    block(0x718000, 0x71FFFF, "South Korea", &["HL"]),
+    block(0x720000, 0x727FFF, "North Korea", &["P"]),
+    block(0x728000, 0x72FFFF, "Iraq", &["YI"]),
+    block(0x730000, 0x737FFF, "Iran", &["EP"]),
+    block(0x738000, 0x73FFFF, "Israel", &["4X"]),
+    block(0x740000, 0x747FFF, "Jordan", &["JY"]),
+    block(0x748000, 0x74FFFF, "Lebanon", &["OD"]),
+    block(0x750000, 0x757FFF, "Malaysia", &["9M"]),
+    block(0x758000, 0x75FFFF, "Philippines", &["RP"]),
+    block(0x760000, 0x767FFF, "Pakistan", &["AP"]),
+    block(0x768000, 0x76FFFF, "Singapore", &["9V"]),
+    block(0x770000, 0x777FFF, "Sri Lanka", &["4R"]),
+    block(0x778000, 0x77FFFF, "Syria", &["YK"]),
+    block(0x780000, 0x7BFFFF, "China", &["B"]),
+    block(0x7C0000, 0x7FFFFF, "Australia", &["VH"]),
+    block(0x800000, 0x83FFFF, "India", &["VT"]),
+    block(0x840000, 0x87FFFF, "Japan", &["JA"]),
+    block(0x880000, 0x887FFF, "Thailand", &["HS"]),
+    block(0x888000, 0x88FFFF, "Vietnam", &["VN"]),
+    block(0x890000, 0x890FFF, "Yemen", &["7O"]),
+    block(0x894000, 0x894FFF, "Bahrain", &["A9C"]),
+    block(0x895000, 0x8953FF, "Brunei", &["V8"]),
+    block(0x896000, 0x896FFF, "United Arab Emirates", &["A6"]),
+    block(0x897000, 0x8973FF, "Solomon Islands", &["H4"]),
+    block(0x898000, 0x898FFF, "Papua New Guinea", &["P2"]),
+    block(0x899000, 0x8993FF, "Taiwan", &["B"]),
+    block(0x8A0000, 0x8A7FFF, "Indonesia", &["PK"]),
+    block(0x900000, 0x9003FF, "Marshall Islands", &["V7"]),
+    block(0x901000, 0x9013FF, "Cook Islands", &["E5"]),
+    block(0x902000, 0x9023FF, "Samoa", &["5W"]),
+    block(0xA00000, 0xAFFFFF, "United States", &["N"]),
+    block(0xC00000, 0xC3FFFF, "Canada", &["C"]),
+    block(0xC80000, 0xC87FFF, "New Zealand", &["ZK"]),
+    block(0xC88000, 0xC88FFF, "Fiji", &["DQ"]),
+    block(0xC8A000, 0xC8A3FF, "Nauru", &["C2"]),
+    block(0xC8C000, 0xC8C3FF, "Saint Lucia", &["J6"]),
+    block(0xC8D000, 0xC8D3FF, "Tonga", &["A3"]),
+    block(0xC8E000, 0xC8E3FF, "Kiribati", &["T3"]),
+    block(0xC90000, 0xC903FF, "Vanuatu", &["YJ"]),
+    block(0xE00000, 0xE3FFFF, "Argentina", &["LV", "LQ"]),
+    block(
+        0xE40000,
+        0xE7FFFF,
+        "Brazil",
+        &["PP", "PR", "PS", "PT", "PU"],
+    ),
+    block(0xE80000, 0xE80FFF, "Chile", &["CC"]),
+    block(0xE84000, 0xE84FFF, "Ecuador", &["HC"]),
+    block(0xE88000, 0xE88FFF, "Paraguay", &["ZP"]),
+    block(0xE8C000, 0xE8CFFF, "Peru", &["OB"]),
+    block(0xE90000, 0xE90FFF, "Uruguay", &["CX"]),
+    block(0xE94000, 0xE94FFF, "Bolivia", &["CP"]),
+];
+
+/// First address of the reserved ICAO block used for temporary and special addresses
+const ICAO_RESERVED_START: u32 = 0xF00000;
+
+// US N-numbers: N1 to N99999 with up to two trailing letters, I and O excluded
+const US_FIRST: u32 = 0xA00001;
+const US_LAST: u32 = 0xADF7C7;
+const US_LETTERS: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ";
+/// The number itself plus every one and two letter suffix
+const US_SUFFIX_SIZE: u32 = 1 + 24 * (1 + 24);
+const US_BUCKET4_SIZE: u32 = 1 + 24 + 10;
+const US_BUCKET3_SIZE: u32 = 10 * US_BUCKET4_SIZE + US_SUFFIX_SIZE;
+const US_BUCKET2_SIZE: u32 = 10 * US_BUCKET3_SIZE + US_SUFFIX_SIZE;
+const US_BUCKET1_SIZE: u32 = 10 * US_BUCKET2_SIZE + US_SUFFIX_SIZE;
+
+// Canadian registrations: C-FAAA to C-FZZZ followed by C-GAAA to C-GZZZ
+const CANADA_FIRST: u32 = 0xC00001;
+const CANADA_SERIES_SIZE: u32 = 26 * 26 * 26;
+
+/// How an address was assigned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressKind {
+    /// Inside a state's ICAO allocation
+    Icao,
+    /// Inside the ICAO block reserved for temporary and special addresses
+    IcaoReserved,
+    /// Outside every allocated block
+    Unallocated,
+    /// Marked as non-ICAO (`~` prefix), such as TIS-B and ADS-R targets
+    NonIcao,
+    /// All zero or all one bits, used by transponders that hide their identity
+    Anonymous,
+}
+
+/// Outcome of comparing a registration against an address
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegistrationCheck {
+    /// The registration is the one the address encodes, or carries the
+    /// nationality mark of the allocating state
+    Consistent,
+    /// The registration contradicts the address, a sign of a spoofed or
+    /// anonymised transponder (e.g. the FAA Privacy ICAO Address programme)
+    Mismatch,
+    /// Not enough is known about the address to compare
+    Unknown,
+}
+
+/// ICAO 24-bit aircraft address, the `hex` field of position data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Icao24 {
+    address: u32,
+    non_icao: bool,
+}
+
+impl Icao24 {
+    /// Wraps a 24-bit address.
+    /// # Arguments
+    ///   * `address` - Address, at most `0xFFFFFF`
+    /// # Returns
+    ///   An `Icao24` on success or a `FlightRadarError::Parameter` on failure.
+    pub fn new(address: u32) -> Result<Self, FlightRadarError> {
+        if address > 0xFFFFFF {
+            return Err(FlightRadarError::Parameter(format!(
+                "ICAO Address: {:X}",
+                address
+            )));
+        }
+        Ok(Icao24 {
+            address,
+            non_icao: false,
+        })
+    }
+
+    /// Numeric address
+    pub fn address(&self) -> u32 {
+        self.address
+    }
+
+    /// How the address was assigned
+    pub fn kind(&self) -> AddressKind {
+        if self.non_icao {
+            AddressKind::NonIcao
+        } else if self.address == 0 || self.address == 0xFFFFFF {
+            AddressKind::Anonymous
+        } else if self.address >= ICAO_RESERVED_START {
+            AddressKind::IcaoReserved
+        } else if self.block().is_some() {
+            AddressKind::Icao
+        } else {
+            AddressKind::Unallocated
+        }
+    }
+
+    /// Allocation block the address falls in
+    pub fn block(&self) -> Option<&'static AddressBlock> {
+        if self.non_icao {
+            return None;
+        }
+        let index = ADDRESS_BLOCKS.partition_point(|b| b.end < self.address);
+        ADDRESS_BLOCKS
+            .get(index)
+            .filter(|b| b.start <= self.address)
+    }
+
+    /// Name of the state the address is allocated to
+    pub fn country(&self) -> Option<&'static str> {
+        self.block().map(|b| b.country)
+    }
+
+    /// Registration encoded by the address, for states that assign addresses
+    /// algorithmically (United States and Canada).
+    /// # Returns
+    ///   The registration, e.g. `N12345` or `C-FABC`, when a formula exists
+    pub fn registration(&self) -> Option<String> {
+        if self.non_icao {
+            return None;
+        }
+        us_registration(self.address).or_else(|| canada_registration(self.address))
+    }
+
+    /// Address assigned algorithmically to a registration.
+    /// # Arguments
+    ///   * `registration` - US N-number or Canadian `C-F` / `C-G` registration
+    /// # Returns
+    ///   The `Icao24` when the registration is valid in a state with a formula
+    pub fn from_registration(registration: &str) -> Option<Self> {
+        let registration = registration.trim().to_ascii_uppercase();
+        us_address(&registration)
+            .or_else(|| canada_address(&registration))
+            .map(|address| Icao24 {
+                address,
+                non_icao: false,
+            })
+    }
+
+    /// Compares a registration against the address.
+    /// # Arguments
+    ///   * `registration` - Registration reported for the aircraft, such as `FullLiveFlightData.reg`
+    /// # Returns
+    ///   The `RegistrationCheck` outcome
+    pub fn check_registration(&self, registration: &str) -> RegistrationCheck {
+        let registration = registration.trim().to_ascii_uppercase();
+        if registration.is_empty() || self.kind() != AddressKind::Icao {
+            return RegistrationCheck::Unknown;
+        }
+
+        if let Some(expected) = self.registration() {
+            return if expected == registration {
+                RegistrationCheck::Consistent
+            } else {
+                RegistrationCheck::Mismatch
+            };
+        }
+
+        let Some(block) = self.block() else {
+            return RegistrationCheck::Unknown;
+        };
+        let consistent = match registration.split_once('-') {
+            Some((mark, _)) => block.registration_prefixes.contains(&mark),
+            None => block
+                .registration_prefixes
+                .iter()
+                .any(|p| registration.starts_with(p)),
+        };
+        if consistent {
+            RegistrationCheck::Consistent
+        } else {
+            RegistrationCheck::Mismatch
+        }
+    }
+}
+
+impl FromStr for Icao24 {
+    type Err = FlightRadarError;
+
+    /// Parses six hex digits, a leading `~` marks a non-ICAO address.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        let (hex, non_icao) = match trimmed.strip_prefix('~') {
+            Some(hex) => (hex, true),
+            None => (trimmed, false),
+        };
+        if hex.is_empty() || hex.len() > 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(FlightRadarError::Parameter(format!("ICAO Address: {}", s)));
+        }
+
+        let address = u32::from_str_radix(hex, 16)
+            .map_err(|_| FlightRadarError::Parameter(format!("ICAO Address: {}", s)))?;
+        Ok(Icao24 { address, non_icao })
+    }
+}
+
+impl fmt::Display for Icao24 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.non_icao {
+            write!(f, "~")?;
+        }
+        write!(f, "{:06X}", self.address)
+    }
+}
+
+impl FullLiveFlightData {
+    /// Parsed `hex` field
+    /// # Returns
+    ///   An `Icao24` on success or a `FlightRadarError` on failure.
+    pub fn icao24(&self) -> Result<Icao24, FlightRadarError> {
+        self.hex.parse()
+    }
+}
+
+impl LightLiveFlightData {
+    /// Parsed `hex` field
+    /// # Returns
+    ///   An `Icao24` on success or a `FlightRadarError` on failure.
+    pub fn icao24(&self) -> Result<Icao24, FlightRadarError> {
+        self.hex.parse()
+    }
+}
+
+fn us_letter_index(c: u8) -> Option<u32> {
+    US_LETTERS.iter().position(|l| *l == c).map(|i| i as u32)
+}
+
+/// Offset of a zero to two letter suffix within a suffix block
+fn us_suffix_offset(suffix: &[u8]) -> Option<u32> {
+    match suffix {
+        [] => Some(0),
+        [first] => Some(us_letter_index(*first)? * 25 + 1),
+        [first, second] => Some(us_letter_index(*first)? * 25 + 1 + us_letter_index(*second)? + 1),
+        _ => None,
+    }
+}
+
+fn us_suffix(offset: u32) -> String {
+    if offset == 0 {
+        return String::new();
+    }
+    let offset = offset - 1;
+    let mut suffix = String::from(US_LETTERS[(offset / 25) as usize] as char);
+    if offset % 25 != 0 {
+        suffix.push(US_LETTERS[(offset % 25 - 1) as usize] as char);
+    }
+    suffix
+}
+
+fn us_registration(address: u32) -> Option<String> {
+    if !(US_FIRST..=US_LAST).contains(&address) {
+        return None;
+    }
+
+    let mut offset = address - US_FIRST;
+    let mut registration = format!("N{}", offset / US_BUCKET1_SIZE + 1);
+    offset %= US_BUCKET1_SIZE;
+
+    for bucket in [US_BUCKET2_SIZE, US_BUCKET3_SIZE, US_BUCKET4_SIZE] {
+        if offset < US_SUFFIX_SIZE {
+            registration.push_str(&us_suffix(offset));
+            return Some(registration);
+        }
+        offset -= US_SUFFIX_SIZE;
+        registration.push_str(&(offset / bucket).to_string());
+        offset %= bucket;
+    }
+
+    // Five digits so far at most one more character, a letter or a digit
+    match offset {
+        0 => {}
+        1..=24 => registration.push(US_LETTERS[(offset - 1) as usize] as char),
+        _ => registration.push_str(&(offset - 25).to_string()),
+    }
+    Some(registration)
+}
+
+fn us_address(registration: &str) -> Option<u32> {
+    let rest = registration.strip_prefix('N')?.as_bytes();
+    let first = *rest.first()?;
+    if !(b'1'..=b'9').contains(&first) || rest.len() > 5 {
+        return None;
+    }
+
+    let mut address = US_FIRST + (first - b'1') as u32 * US_BUCKET1_SIZE;
+    for (i, bucket) in [US_BUCKET2_SIZE, US_BUCKET3_SIZE, US_BUCKET4_SIZE]
+        .into_iter()
+        .enumerate()
+    {
+        let rest = &rest[i + 1..];
+        match rest.first() {
+            None => return Some(address),
+            Some(c) if c.is_ascii_digit() => {
+                address += US_SUFFIX_SIZE + (c - b'0') as u32 * bucket;
+            }
+            Some(_) => return Some(address + us_suffix_offset(rest)?),
+        }
+    }
+
+    match rest.get(4) {
+        None => Some(address),
+        Some(c) if c.is_ascii_digit() => Some(address + 1 + 24 + (c - b'0') as u32),
+        Some(c) => Some(address + 1 + us_letter_index(*c)?),
+    }
+}
+
+fn canada_registration(address: u32) -> Option<String> {
+    if !(CANADA_FIRST..CANADA_FIRST + 2 * CANADA_SERIES_SIZE).contains(&address) {
+        return None;
+    }
+
+    let offset = address - CANADA_FIRST;
+    let series = if offset < CANADA_SERIES_SIZE {
+        'F'
+    } else {
+        'G'
+    };
+    let offset = offset % CANADA_SERIES_SIZE;
+    let letter = |n: u32| (b'A' + n as u8) as char;
+    Some(format!(
+        "C-{}{}{}{}",
+        series,
+        letter(offset / (26 * 26)),
+        letter(offset / 26 % 26),
+        letter(offset % 26)
+    ))
+}
+
+fn canada_address(registration: &str) -> Option<u32> {
+    let rest = registration.strip_prefix("C-")?.as_bytes();
+    let [series, letters @ ..] = rest else {
+        return None;
+    };
+    let series = match series {
+        b'F' => 0,
+        b'G' => 1,
+        _ => return None,
+    };
+    if letters.len() != 3 || !letters.iter().all(|c| c.is_ascii_uppercase()) {
+        return None;
+    }
+
+    let offset = letters
+        .iter()
+        .fold(0, |acc, c| acc * 26 + (c - b'A') as u32);
+    Some(CANADA_FIRST + series * CANADA_SERIES_SIZE + offset)
+}
@@ -1,6 +1,51 @@
+/// Embedded ICAO Aircraft Type Dataset
+#[cfg(feature = "aircraft-db")]
+pub mod aircraft_db;
+/// Airport Arrivals and Departures Boards
+pub mod board;
+/// Response Caching
+pub mod cache;
+/// Record and Replay of API Interactions
+pub mod cassette;
 /// API Wrapper Client
 pub mod client;
+/// Arrow and Parquet Output
+#[cfg(feature = "parquet")]
+pub mod columnar;
+/// Airport and Airline Enrichment of Live Flights
+pub mod enrich;
 /// Custom Package Errors
 pub mod error;
+/// Arrival Time Estimation
+pub mod eta;
+/// Go-Around, Holding and Diversion Detection
+pub mod events;
+/// GeoJSON, KML and GPX Export for Tracks
+pub mod export;
+/// Typed Query Filters
+pub mod filter;
 /// Helpers for Tracks Functionality
 pub mod flight_tracks_helper;
+/// Great-Circle Geometry Helpers
+pub mod geo;
+/// ICAO 24-bit Address Decoding
+pub mod icao24;
+/// Request Rate Limiting
+pub mod rate_limit;
+/// Fixed Time Grid Resampling for Tracks
+pub mod resample;
+/// Runway Database and Runway Usage Inference
+pub mod runways;
+/// Closest Point of Approach and Conflict Detection
+pub mod separation;
+/// Track Smoothing and Outlier Rejection
+pub mod smoothing;
+/// Nearest, Radius and Route Queries over Snapshots
+pub mod spatial;
+/// Local SQLite Archive
+#[cfg(feature = "sqlite")]
+pub mod store;
+/// Historic Snapshot Sweeps
+pub mod sweep;
+/// Track Reconstruction from Snapshots
+pub mod track_builder;
@@ -0,0 +1,62 @@
+/// Token bucket rate limiting for API requests
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Token bucket allowing bursts up to its capacity
+pub struct RateLimiter {
+    capacity: f64,
+    /// Tokens added per second
+    refill_rate: f64,
+    state: Mutex<BucketState>,
+}
+
+struct BucketState {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl RateLimiter {
+    /// Creates a new limiter that starts full.
+    /// # Arguments
+    ///   * `requests` - Requests allowed per period, also the burst size
+    ///   * `per` - Length of the period
+    pub fn new(requests: u32, per: Duration) -> Self {
+        let capacity = requests.max(1) as f64;
+        RateLimiter {
+            capacity,
+            refill_rate: capacity / per.as_secs_f64().max(f64::EPSILON),
+            state: Mutex::new(BucketState {
+                tokens: capacity,
+                updated: Instant::now(),
+            }),
+        }
+    }
+
+    /// Takes a token if one is available.
+    /// # Returns
+    ///   `Ok(())` when the request may proceed, otherwise the time until a token is available
+    pub fn try_acquire(&self) -> Result<(), Duration> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        let elapsed = now.duration_since(state.updated).as_secs_f64();
+        state.tokens = (state.tokens + elapsed * self.refill_rate).min(self.capacity);
+        state.updated = now;
+
+        if state.tokens >= 1.0 {
+            state.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - state.tokens) / self.refill_rate,
+            ))
+        }
+    }
+
+    /// Blocks the current thread until a token is available and takes it.
+    pub fn acquire(&self) {
+        while let Err(wait) = self.try_acquire() {
+            thread::sleep(wait);
+        }
+    }
+}
@@ -0,0 +1,152 @@
+/// Fixed time grid resampling for the `flight-tracks` API Endpoint resultant data
+use crate::client::{Flight, Track};
+use crate::error::FlightRadarError;
+use crate::geo;
+use chrono::{DateTime, Duration, Utc};
+
+/// Largest sample spacing (in seconds) interpolated across by `Flight::resample`
+pub const DEFAULT_MAX_GAP_SECS: i64 = 300;
+
+/// Interpolated aircraft state at a grid timestamp
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TrackState {
+    pub lat: f64,
+    pub lon: f64,
+    pub alt: f64,
+    pub gspeed: f64,
+    pub vspeed: f64,
+    pub track: f64,
+}
+
+/// Single point on the resampled time grid
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResampledPoint {
+    pub timestamp: DateTime<Utc>,
+    /// `None` when the grid point falls inside a gap larger than the threshold
+    pub state: Option<TrackState>,
+}
+
+/// Flight tracks aligned to a fixed time grid
+#[derive(Debug, Clone)]
+pub struct ResampledFlight {
+    pub id: String,
+    pub interval: Duration,
+    pub points: Vec<ResampledPoint>,
+}
+
+impl ResampledFlight {
+    /// Number of grid points that fell inside a gap
+    pub fn gap_count(&self) -> usize {
+        self.points.iter().filter(|p| p.state.is_none()).count()
+    }
+}
+
+impl Flight {
+    /// Resample tracks onto a fixed time grid, flagging gaps longer than
+    /// `DEFAULT_MAX_GAP_SECS`.
+    /// # Arguments
+    ///   * `interval` - Spacing of the time grid
+    /// # Returns
+    ///   A `ResampledFlight` on success or a `FlightRadarError` on failure.
+    pub fn resample(&self, interval: Duration) -> Result<ResampledFlight, FlightRadarError> {
+        self.resample_with_max_gap(interval, Duration::seconds(DEFAULT_MAX_GAP_SECS))
+    }
+
+    /// Resample tracks onto a fixed time grid.
+    ///
+    /// Grid timestamps are multiples of `interval` since the Unix epoch so two
+    /// flights resampled with the same interval line up point for point.
+    /// Position follows the great circle between samples, heading takes the
+    /// shortest turn and the remaining values are interpolated linearly.
+    /// # Arguments
+    ///   * `interval` - Spacing of the time grid
+    ///   * `max_gap` - Largest sample spacing that is still interpolated across
+    /// # Returns
+    ///   A `ResampledFlight` on success or a `FlightRadarError` on failure.
+    pub fn resample_with_max_gap(
+        &self,
+        interval: Duration,
+        max_gap: Duration,
+    ) -> Result<ResampledFlight, FlightRadarError> {
+        let step = interval.num_milliseconds();
+        if step <= 0 {
+            return Err(FlightRadarError::Parameter(format!(
+                "Resample Interval: {}",
+                interval
+            )));
+        }
+
+        // Order samples by time, keeping the first of any duplicate timestamps
+        let mut samples: Vec<(i64, &Track)> = Vec::with_capacity(self.tracks.len());
+        for track in self.tracks.iter() {
+            samples.push((track.parsed_timestamp()?.timestamp_millis(), track));
+        }
+        samples.sort_by_key(|(time, _)| *time);
+        samples.dedup_by_key(|(time, _)| *time);
+
+        let mut points = Vec::new();
+        if let (Some(first), Some(last)) = (samples.first(), samples.last()) {
+            let mut time = (first.0 + step - 1).div_euclid(step) * step;
+            let mut segment = 0;
+
+            while time <= last.0 {
+                while segment + 1 < samples.len() && samples[segment + 1].0 <= time {
+                    segment += 1;
+                }
+
+                let (start_time, start) = samples[segment];
+                let state = if start_time == time {
+                    Some(state_from_track(start))
+                } else {
+                    let (end_time, end) = samples[segment + 1];
+                    if end_time - start_time > max_gap.num_milliseconds() {
+                        None
+                    } else {
+                        let fraction = (time - start_time) as f64 / (end_time - start_time) as f64;
+                        Some(interpolate(start, end, fraction))
+                    }
+                };
+
+                points.push(ResampledPoint {
+                    timestamp: DateTime::from_timestamp_millis(time).ok_or_else(|| {
+                        FlightRadarError::Parsing(format!("Grid Timestamp: {}", time))
+                    })?,
+                    state,
+                });
+                time += step;
+            }
+        }
+
+        Ok(ResampledFlight {
+            id: self.id.clone(),
+            interval,
+            points,
+        })
+    }
+}
+
+fn state_from_track(track: &Track) -> TrackState {
+    TrackState {
+        lat: track.lat,
+        lon: track.lon,
+        alt: track.alt as f64,
+        gspeed: track.gspeed as f64,
+        vspeed: track.vspeed as f64,
+        track: track.track as f64,
+    }
+}
+
+fn interpolate(start: &Track, end: &Track, fraction: f64) -> TrackState {
+    let linear = |a: f64, b: f64| a + (b - a) * fraction;
+    let (lat, lon) =
+        geo::interpolate_great_circle(start.lat, start.lon, end.lat, end.lon, fraction);
+
+    TrackState {
+        lat,
+        lon,
+        alt: linear(start.alt as f64, end.alt as f64),
+        gspeed: linear(start.gspeed as f64, end.gspeed as f64),
+        vspeed: linear(start.vspeed as f64, end.vspeed as f64),
+        track: geo::interpolate_heading(start.track as f64, end.track as f64, fraction),
+    }
+}
@@ -0,0 +1,342 @@
+/// Runway database and runway usage inference from track geometry
+use crate::client::{Flight, Track};
+use crate::error::FlightRadarError;
+use crate::events::ordered_points;
+use crate::geo;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+const FEET_PER_NM: f64 = 6076.12;
+
+/// One direction of a runway, named after its threshold
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunwayEnd {
+    /// Airport identifier, the ICAO code for most airports
+    pub airport: String,
+    /// Runway designator such as `09L`
+    pub ident: String,
+    /// Threshold latitude
+    pub lat: f64,
+    /// Threshold longitude
+    pub lon: f64,
+    /// True heading flown along the runway from this threshold
+    pub heading: f64,
+    pub elevation_ft: Option<i32>,
+    pub length_ft: f64,
+}
+
+/// Row of the OurAirports `runways.csv` file
+#[derive(Debug, Deserialize)]
+struct RunwayRecord {
+    airport_ident: String,
+    #[serde(deserialize_with = "csv::invalid_option")]
+    length_ft: Option<f64>,
+    #[serde(deserialize_with = "csv::invalid_option")]
+    closed: Option<u8>,
+    le_ident: String,
+    #[serde(deserialize_with = "csv::invalid_option")]
+    le_latitude_deg: Option<f64>,
+    #[serde(deserialize_with = "csv::invalid_option")]
+    le_longitude_deg: Option<f64>,
+    #[serde(deserialize_with = "csv::invalid_option")]
+    le_elevation_ft: Option<i32>,
+    #[serde(rename = "le_heading_degT", deserialize_with = "csv::invalid_option")]
+    le_heading: Option<f64>,
+    he_ident: String,
+    #[serde(deserialize_with = "csv::invalid_option")]
+    he_latitude_deg: Option<f64>,
+    #[serde(deserialize_with = "csv::invalid_option")]
+    he_longitude_deg: Option<f64>,
+    #[serde(deserialize_with = "csv::invalid_option")]
+    he_elevation_ft: Option<i32>,
+    #[serde(rename = "he_heading_degT", deserialize_with = "csv::invalid_option")]
+    he_heading: Option<f64>,
+}
+
+impl RunwayRecord {
+    /// Both runway ends, skipping an end without threshold coordinates
+    fn into_ends(self) -> Vec<RunwayEnd> {
+        let airport = self.airport_ident.trim().to_ascii_uppercase();
+        let low = self.le_latitude_deg.zip(self.le_longitude_deg);
+        let high = self.he_latitude_deg.zip(self.he_longitude_deg);
+
+        // Heading and length follow the thresholds when both are known
+        let between = low.zip(high).map(|((lat1, lon1), (lat2, lon2))| {
+            (
+                geo::initial_bearing(lat1, lon1, lat2, lon2),
+                geo::initial_bearing(lat2, lon2, lat1, lon1),
+                geo::haversine_nm(lat1, lon1, lat2, lon2) * FEET_PER_NM,
+            )
+        });
+        let length_ft = self.length_ft.or(between.map(|b| b.2)).unwrap_or_default();
+
+        let ends = [
+            (
+                self.le_ident,
+                low,
+                self.le_elevation_ft,
+                self.le_heading.or(between.map(|b| b.0)),
+            ),
+            (
+                self.he_ident,
+                high,
+                self.he_elevation_ft,
+                self.he_heading.or(between.map(|b| b.1)),
+            ),
+        ];
+        ends.into_iter()
+            .filter_map(|(ident, position, elevation_ft, heading)| {
+                let (lat, lon) = position?;
+                Some(RunwayEnd {
+                    airport: airport.clone(),
+                    ident: ident.trim().to_string(),
+                    lat,
+                    lon,
+                    heading: geo::normalize_heading(heading?),
+                    elevation_ft,
+                    length_ft,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Runway ends grouped by airport
+#[derive(Debug, Clone, Default)]
+pub struct RunwayDatabase {
+    airports: HashMap<String, Vec<RunwayEnd>>,
+}
+
+impl RunwayDatabase {
+    /// Reads the OurAirports `runways.csv` file.
+    /// # Arguments
+    ///   * `path` - Location of the CSV file
+    /// # Returns
+    ///   A `RunwayDatabase` on success or a `FlightRadarError` on failure.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, FlightRadarError> {
+        Self::from_reader(File::open(path)?)
+    }
+
+    /// Reads runways in the OurAirports CSV layout.
+    ///
+    /// Closed runways and ends without threshold coordinates are left out.
+    /// A missing heading is taken from the bearing between both thresholds.
+    /// # Arguments
+    ///   * `reader` - Source of the CSV data, header row included
+    /// # Returns
+    ///   A `RunwayDatabase` on success or a `FlightRadarError` on failure.
+    pub fn from_reader<R: Read>(reader: R) -> Result<Self, FlightRadarError> {
+        let mut database = RunwayDatabase::default();
+        for record in csv::Reader::from_reader(reader).deserialize::<RunwayRecord>() {
+            let record =
+                record.map_err(|e| FlightRadarError::Parsing(format!("Runways: {}", e)))?;
+            if record.closed == Some(1) {
+                continue;
+            }
+            for end in record.into_ends() {
+                database.insert(end);
+            }
+        }
+        Ok(database)
+    }
+
+    /// Adds a runway end, for instance one missing from the dataset
+    pub fn insert(&mut self, end: RunwayEnd) {
+        self.airports
+            .entry(end.airport.to_ascii_uppercase())
+            .or_default()
+            .push(end);
+    }
+
+    /// Runway ends of an airport, empty when the airport is unknown
+    pub fn airport(&self, ident: &str) -> &[RunwayEnd] {
+        self.airports
+            .get(&ident.trim().to_ascii_uppercase())
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Number of airports with runways
+    pub fn len(&self) -> usize {
+        self.airports.len()
+    }
+
+    /// Whether no runway has been loaded
+    pub fn is_empty(&self) -> bool {
+        self.airports.is_empty()
+    }
+
+    /// Infers the landing runway with the default `RunwayMatcher`.
+    /// # Arguments
+    ///   * `flight` - Flight whose tracks end at the airport
+    ///   * `airport` - Airport identifier
+    /// # Returns
+    ///   The `RunwayUsage` when a runway matches the final approach
+    pub fn infer_landing(&self, flight: &Flight, airport: &str) -> Option<RunwayUsage> {
+        RunwayMatcher::default().landing(self.airport(airport), flight)
+    }
+
+    /// Infers the departure runway with the default `RunwayMatcher`.
+    /// # Arguments
+    ///   * `flight` - Flight whose tracks start at the airport
+    ///   * `airport` - Airport identifier
+    /// # Returns
+    ///   The `RunwayUsage` when a runway matches the initial climb
+    pub fn infer_departure(&self, flight: &Flight, airport: &str) -> Option<RunwayUsage> {
+        RunwayMatcher::default().departure(self.airport(airport), flight)
+    }
+}
+
+/// Direction of a runway operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum RunwayOperation {
+    Departure,
+    Landing,
+}
+
+/// Runway a flight used, with how well the track fitted it
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunwayUsage {
+    pub runway: RunwayEnd,
+    pub operation: RunwayOperation,
+    /// Time of the matched point nearest the runway
+    pub timestamp: DateTime<Utc>,
+    /// Track points lined up with the runway
+    pub matched_points: usize,
+    /// Mean lateral distance of those points from the centreline in nautical miles
+    pub mean_offset_nm: f64,
+}
+
+/// Geometry limits for matching tracks against runways
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunwayMatcher {
+    /// Distance before the threshold, or past the runway end on departure,
+    /// within which points are considered
+    pub max_distance_nm: f64,
+    /// Height above the threshold below which points are considered
+    pub max_height_ft: f64,
+    /// Largest difference between track and runway heading
+    pub max_heading_error_deg: f64,
+    /// Largest lateral distance from the extended centreline
+    pub max_offset_nm: f64,
+    /// Fewest lined up points needed for a match
+    pub min_points: usize,
+}
+
+impl Default for RunwayMatcher {
+    fn default() -> Self {
+        RunwayMatcher {
+            max_distance_nm: 8.0,
+            max_height_ft: 3000.0,
+            max_heading_error_deg: 15.0,
+            max_offset_nm: 0.5,
+            min_points: 2,
+        }
+    }
+}
+
+impl RunwayMatcher {
+    /// Matches the final approach and landing roll against runway ends.
+    ///
+    /// Points up to the final touchdown are projected on each runway's
+    /// extended centreline. Among runways collecting at least `min_points`
+    /// aligned points that lose height along it, the one with the smallest
+    /// mean lateral offset wins, which separates parallel runways.
+    /// # Arguments
+    ///   * `runways` - Runway ends of the arrival airport
+    ///   * `flight` - Flight whose tracks end at the airport
+    /// # Returns
+    ///   The `RunwayUsage` when a runway matches
+    pub fn landing(&self, runways: &[RunwayEnd], flight: &Flight) -> Option<RunwayUsage> {
+        let points = ordered_points(flight);
+        // Keep the roll after the last airborne point, drop taxiing after it
+        let end = match points.iter().rposition(|(_, t)| !t.on_ground()) {
+            Some(last_airborne) => (last_airborne + 3).min(points.len()),
+            None => points.len(),
+        };
+        self.best_match(runways, &points[..end], RunwayOperation::Landing)
+    }
+
+    /// Matches the take-off roll and initial climb against runway ends.
+    /// # Arguments
+    ///   * `runways` - Runway ends of the departure airport
+    ///   * `flight` - Flight whose tracks start at the airport
+    /// # Returns
+    ///   The `RunwayUsage` when a runway matches
+    pub fn departure(&self, runways: &[RunwayEnd], flight: &Flight) -> Option<RunwayUsage> {
+        let points = ordered_points(flight);
+        // Keep the roll before the first airborne point, drop taxiing before it
+        let start = match points.iter().position(|(_, t)| !t.on_ground()) {
+            Some(first_airborne) => first_airborne.saturating_sub(2),
+            None => 0,
+        };
+        self.best_match(runways, &points[start..], RunwayOperation::Departure)
+    }
+
+    fn best_match(
+        &self,
+        runways: &[RunwayEnd],
+        points: &[(DateTime<Utc>, &Track)],
+        operation: RunwayOperation,
+    ) -> Option<RunwayUsage> {
+        runways
+            .iter()
+            .filter_map(|runway| self.match_runway(runway, points, operation))
+            .min_by(|a, b| a.mean_offset_nm.total_cmp(&b.mean_offset_nm))
+    }
+
+    fn match_runway(
+        &self,
+        runway: &RunwayEnd,
+        points: &[(DateTime<Utc>, &Track)],
+        operation: RunwayOperation,
+    ) -> Option<RunwayUsage> {
+        let heading = runway.heading.to_radians();
+        let length_nm = runway.length_ft / FEET_PER_NM;
+        let (min_along, max_along) = match operation {
+            RunwayOperation::Landing => (-self.max_distance_nm, length_nm),
+            RunwayOperation::Departure => (0.0, length_nm + self.max_distance_nm),
+        };
+        let elevation = runway.elevation_ft.unwrap_or_default() as f64;
+
+        let matched: Vec<(DateTime<Utc>, f64, u32)> = points
+            .iter()
+            .filter(|(_, t)| {
+                t.alt as f64 - elevation <= self.max_height_ft
+                    && geo::heading_difference(runway.heading, t.track as f64).abs()
+                        <= self.max_heading_error_deg
+            })
+            .filter_map(|&(time, t)| {
+                let (east, north) = geo::local_offset_nm(runway.lat, runway.lon, t.lat, t.lon);
+                let along = east * heading.sin() + north * heading.cos();
+                let offset = (east * heading.cos() - north * heading.sin()).abs();
+                (along >= min_along && along <= max_along && offset <= self.max_offset_nm)
+                    .then_some((time, offset, t.alt))
+            })
+            .collect();
+
+        let (first, last) = (matched.first()?, matched.last()?);
+        // A landing loses height along the runway and a departure gains it
+        let direction_fits = match operation {
+            RunwayOperation::Landing => first.2 > last.2,
+            RunwayOperation::Departure => last.2 > first.2,
+        };
+        if matched.len() < self.min_points || !direction_fits {
+            return None;
+        }
+        let timestamp = match operation {
+            RunwayOperation::Landing => last.0,
+            RunwayOperation::Departure => first.0,
+        };
+        Some(RunwayUsage {
+            runway: runway.clone(),
+            operation,
+            timestamp,
+            matched_points: matched.len(),
+            mean_offset_nm: matched.iter().map(|m| m.1).sum::<f64>() / matched.len() as f64,
+        })
+    }
+}
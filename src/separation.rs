@@ -0,0 +1,202 @@
+/// Closest point of approach and conflict detection between live aircraft
+use crate::client::FullLiveFlightData;
+use crate::geo;
+use chrono::Duration;
+use rstar::{primitives::GeomWithData, RTree};
+
+/// Default horizontal separation minimum in nautical miles
+pub const DEFAULT_HORIZONTAL_NM: f64 = 5.0;
+/// Default vertical separation minimum in feet
+pub const DEFAULT_VERTICAL_FT: f64 = 1000.0;
+/// Default look-ahead time in seconds
+pub const DEFAULT_LOOKAHEAD_SECS: i64 = 300;
+
+/// Separation minima and projection horizon used for conflict detection
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SeparationMinima {
+    pub horizontal_nm: f64,
+    pub vertical_ft: f64,
+    /// How far ahead trajectories are projected
+    pub lookahead: Duration,
+}
+
+impl Default for SeparationMinima {
+    fn default() -> Self {
+        SeparationMinima {
+            horizontal_nm: DEFAULT_HORIZONTAL_NM,
+            vertical_ft: DEFAULT_VERTICAL_FT,
+            lookahead: Duration::seconds(DEFAULT_LOOKAHEAD_SECS),
+        }
+    }
+}
+
+/// Geometry of two aircraft at their closest point of approach
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClosestApproach {
+    /// Time from now until the closest point, zero when already diverging
+    pub time_to_cpa: Duration,
+    pub horizontal_nm: f64,
+    pub vertical_ft: f64,
+}
+
+/// Pair of aircraft predicted to breach the separation minima
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    pub first: String,
+    pub second: String,
+    pub cpa: ClosestApproach,
+    /// Time from now until both minima are breached at once
+    pub time_to_loss: Duration,
+}
+
+/// Closest point of approach of two aircraft flying straight at constant speed.
+///
+/// Trajectories are projected on a flat plane centred between both aircraft
+/// and the horizontal closest point is searched within `lookahead`.
+/// # Arguments
+///   * `a`, `b` - Aircraft states from the `flight-positions` endpoint
+///   * `lookahead` - Projection horizon
+/// # Returns
+///   The `ClosestApproach` of the pair
+pub fn closest_point_of_approach(
+    a: &FullLiveFlightData,
+    b: &FullLiveFlightData,
+    lookahead: Duration,
+) -> ClosestApproach {
+    let relative = Relative::new(a, b);
+    let horizon = lookahead.num_milliseconds().max(0) as f64 / 1000.0;
+
+    let dv2 = relative.dvx.powi(2) + relative.dvy.powi(2);
+    let t = if dv2 > f64::EPSILON {
+        (-(relative.dx * relative.dvx + relative.dy * relative.dvy) / dv2).clamp(0.0, horizon)
+    } else {
+        0.0
+    };
+
+    ClosestApproach {
+        time_to_cpa: Duration::milliseconds((t * 1000.0).round() as i64),
+        horizontal_nm: ((relative.dx + relative.dvx * t).powi(2)
+            + (relative.dy + relative.dvy * t).powi(2))
+        .sqrt(),
+        vertical_ft: (relative.dz + relative.dvz * t).abs(),
+    }
+}
+
+/// Pairs of airborne aircraft predicted to breach the minima within the
+/// look-ahead time.
+///
+/// Candidate pairs come from a spatial index, so only aircraft that could
+/// close to within the horizontal minimum are compared. Aircraft reporting an
+/// altitude of zero are on the ground and ignored.
+/// # Arguments
+///   * `flights` - Aircraft states from the `flight-positions` endpoint
+///   * `minima` - Separation minima and look-ahead time
+/// # Returns
+///   The `Conflict`s sorted by time to loss of separation
+pub fn detect_conflicts(
+    flights: &[FullLiveFlightData],
+    minima: &SeparationMinima,
+) -> Vec<Conflict> {
+    let airborne: Vec<&FullLiveFlightData> = flights.iter().filter(|f| f.alt > 0).collect();
+    let horizon = minima.lookahead.num_milliseconds().max(0) as f64 / 1000.0;
+    let max_gspeed = airborne.iter().map(|f| f.gspeed).max().unwrap_or(0) as f64;
+
+    let tree = RTree::bulk_load(
+        airborne
+            .iter()
+            .enumerate()
+            .map(|(i, f)| GeomWithData::new(geo::to_cartesian_nm(f.lat, f.lon), i))
+            .collect(),
+    );
+
+    let mut conflicts = Vec::new();
+    for (i, a) in airborne.iter().enumerate() {
+        // Furthest apart two aircraft can start and still meet within the horizon
+        let reach = minima.horizontal_nm + (a.gspeed as f64 + max_gspeed) / 3600.0 * horizon;
+        let centre = geo::to_cartesian_nm(a.lat, a.lon);
+
+        for candidate in tree.locate_within_distance(centre, reach * reach) {
+            let j = candidate.data;
+            if j <= i {
+                continue;
+            }
+
+            let b = airborne[j];
+            if let Some(time_to_loss) = Relative::new(a, b).loss_of_separation(minima, horizon) {
+                conflicts.push(Conflict {
+                    first: a.fr24_id.to_string(),
+                    second: b.fr24_id.to_string(),
+                    cpa: closest_point_of_approach(a, b, minima.lookahead),
+                    time_to_loss: Duration::milliseconds((time_to_loss * 1000.0).round() as i64),
+                });
+            }
+        }
+    }
+
+    conflicts.sort_by_key(|c| c.time_to_loss);
+    conflicts
+}
+
+/// Position (nm, ft) and velocity (nm/s, ft/s) of one aircraft relative to another
+struct Relative {
+    dx: f64,
+    dy: f64,
+    dz: f64,
+    dvx: f64,
+    dvy: f64,
+    dvz: f64,
+}
+
+impl Relative {
+    fn new(a: &FullLiveFlightData, b: &FullLiveFlightData) -> Self {
+        let (dx, dy) = geo::local_offset_nm(a.lat, a.lon, b.lat, b.lon);
+        let (avx, avy) = velocity(a);
+        let (bvx, bvy) = velocity(b);
+
+        Relative {
+            dx,
+            dy,
+            dz: b.alt as f64 - a.alt as f64,
+            dvx: bvx - avx,
+            dvy: bvy - avy,
+            dvz: (b.vspeed - a.vspeed) as f64 / 60.0,
+        }
+    }
+
+    /// Earliest time within `[0, horizon]` seconds at which both minima are breached
+    fn loss_of_separation(&self, minima: &SeparationMinima, horizon: f64) -> Option<f64> {
+        // Horizontal: |d + v t| < H, a quadratic in t
+        let a = self.dvx.powi(2) + self.dvy.powi(2);
+        let b = 2.0 * (self.dx * self.dvx + self.dy * self.dvy);
+        let c = self.dx.powi(2) + self.dy.powi(2) - minima.horizontal_nm.powi(2);
+        let horizontal = if a <= f64::EPSILON {
+            (c < 0.0).then_some((f64::NEG_INFINITY, f64::INFINITY))
+        } else {
+            let discriminant = b * b - 4.0 * a * c;
+            (discriminant > 0.0).then(|| {
+                let root = discriminant.sqrt();
+                ((-b - root) / (2.0 * a), (-b + root) / (2.0 * a))
+            })
+        }?;
+
+        // Vertical: |dz + vz t| < V, linear in t
+        let vertical = if self.dvz.abs() <= f64::EPSILON {
+            (self.dz.abs() < minima.vertical_ft).then_some((f64::NEG_INFINITY, f64::INFINITY))
+        } else {
+            let t1 = (-minima.vertical_ft - self.dz) / self.dvz;
+            let t2 = (minima.vertical_ft - self.dz) / self.dvz;
+            Some((t1.min(t2), t1.max(t2)))
+        }?;
+
+        let start = horizontal.0.max(vertical.0).max(0.0);
+        let end = horizontal.1.min(vertical.1).min(horizon);
+        (start <= end).then_some(start)
+    }
+}
+
+/// Ground velocity as `(east, north)` in nautical miles per second
+fn velocity(flight: &FullLiveFlightData) -> (f64, f64) {
+    let speed = flight.gspeed as f64 / 3600.0;
+    let track = (flight.track as f64).to_radians();
+    (speed * track.sin(), speed * track.cos())
+}
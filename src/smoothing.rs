@@ -0,0 +1,373 @@
+/// Outlier rejection and Kalman smoothing for the `flight-tracks` API Endpoint resultant data
+use crate::client::{Flight, Track};
+use crate::geo;
+use chrono::{DateTime, Utc};
+
+const METRES_PER_DEGREE: f64 = 60.0 * 1852.0;
+
+/// Outcome for a single point of the input track
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PointFlag {
+    /// Smoothing moved the point less than the tolerance, raw values are kept
+    Kept,
+    /// Position or altitude replaced by the smoothed estimate
+    Smoothed,
+    /// Dropped as a physically impossible jump, duplicate or unreadable timestamp
+    Rejected,
+}
+
+/// Limits, noise levels and source weights of the smoother
+#[derive(Debug, Clone, PartialEq)]
+pub struct SmoothingModel {
+    /// Largest plausible ground speed between two points in knots
+    pub max_speed_kt: f64,
+    /// Largest plausible altitude change in feet per minute
+    pub max_vertical_rate_fpm: f64,
+    /// Largest plausible change of the reported track in degrees per second,
+    /// checked only when both points are above taxi speed
+    pub max_turn_rate_deg_s: f64,
+    /// Rejections in a row after which the track is assumed to have really
+    /// moved and the next point is accepted again
+    pub max_consecutive_rejections: usize,
+    /// Horizontal position noise of an ADS-B fix in metres
+    pub position_sigma_m: f64,
+    /// Altitude noise of an ADS-B report in feet
+    pub alt_sigma_ft: f64,
+    /// Horizontal acceleration noise of the motion model in m/s²
+    pub horizontal_accel_ms2: f64,
+    /// Vertical acceleration noise of the motion model in ft/s²
+    pub vertical_accel_fps2: f64,
+    /// Smallest correction, in metres, reported as `Smoothed`
+    pub position_tolerance_m: f64,
+    /// Smallest correction, in feet, reported as `Smoothed`
+    pub alt_tolerance_ft: f64,
+}
+
+impl Default for SmoothingModel {
+    fn default() -> Self {
+        SmoothingModel {
+            max_speed_kt: 800.0,
+            max_vertical_rate_fpm: 10000.0,
+            max_turn_rate_deg_s: 6.0,
+            max_consecutive_rejections: 3,
+            position_sigma_m: 30.0,
+            alt_sigma_ft: 50.0,
+            horizontal_accel_ms2: 2.0,
+            vertical_accel_fps2: 10.0,
+            position_tolerance_m: 100.0,
+            alt_tolerance_ft: 100.0,
+        }
+    }
+}
+
+/// Flight with its smoothed track and a flag for every input point
+#[derive(Debug, Clone)]
+pub struct SmoothedFlight {
+    /// Kept and smoothed points in time order
+    pub flight: Flight,
+    /// Flag of each point of the input, in input order
+    pub flags: Vec<PointFlag>,
+}
+
+impl SmoothedFlight {
+    /// Number of input points with a given flag
+    pub fn count(&self, flag: PointFlag) -> usize {
+        self.flags.iter().filter(|f| **f == flag).count()
+    }
+}
+
+/// Noise multiplier of a position source, ADS-B being the reference.
+///
+/// MLAT fixes depend on receiver geometry and `ESTIMATED` positions are
+/// extrapolated by FR24, so both are trusted less. Unknown sources sit
+/// between MLAT and estimated positions.
+/// # Arguments
+///   * `source` - `source` value of a track point
+/// # Returns
+///   Factor applied to the position and altitude noise
+pub fn source_noise_factor(source: &str) -> f64 {
+    match source.trim().to_ascii_uppercase().as_str() {
+        "ADSB" | "ADS-B" => 1.0,
+        "UAT" => 1.5,
+        "SATELLITE" => 3.0,
+        "FLARM" => 3.0,
+        "MLAT" => 5.0,
+        "ESTIMATED" => 20.0,
+        _ => 10.0,
+    }
+}
+
+impl SmoothingModel {
+    /// Rejects impossible jumps then smooths the remaining points.
+    ///
+    /// A point is rejected when reaching it from the last accepted point
+    /// needs more than the speed, vertical rate or turn rate limits. The
+    /// first point is checked against the following one instead. Latitude,
+    /// longitude and altitude of the accepted points then go through a
+    /// constant velocity Kalman filter and a Rauch-Tung-Striebel backward
+    /// pass, each fix weighted by `source_noise_factor`.
+    /// # Arguments
+    ///   * `flight` - Flight whose tracks are filtered, in any order
+    /// # Returns
+    ///   A `SmoothedFlight` with one flag per input point
+    pub fn smooth(&self, flight: &Flight) -> SmoothedFlight {
+        let mut flags = vec![PointFlag::Rejected; flight.tracks.len()];
+
+        // Order samples by time, keeping the first of any duplicate timestamps
+        let mut samples: Vec<(DateTime<Utc>, usize)> = flight
+            .tracks
+            .iter()
+            .enumerate()
+            .filter_map(|(i, t)| Some((t.parsed_timestamp().ok()?, i)))
+            .collect();
+        samples.sort();
+        samples.dedup_by_key(|s| s.0);
+
+        let accepted = self.accept(&flight.tracks, &samples);
+        if accepted.is_empty() {
+            return SmoothedFlight {
+                flight: Flight {
+                    id: flight.id.clone(),
+                    tracks: Vec::new(),
+                },
+                flags,
+            };
+        }
+
+        let points: Vec<(f64, &Track)> = accepted
+            .iter()
+            .map(|&(time, i)| {
+                let seconds = (time - accepted[0].0).num_milliseconds() as f64 / 1000.0;
+                (seconds, &flight.tracks[i])
+            })
+            .collect();
+        let smoothed = self.smooth_points(&points);
+
+        let mut tracks = Vec::with_capacity(accepted.len());
+        for (&(_, i), (lat, lon, alt)) in accepted.iter().zip(smoothed) {
+            let raw = &flight.tracks[i];
+            let moved_m = geo::haversine_nm(raw.lat, raw.lon, lat, lon) * 1852.0;
+            let climbed_ft = (alt - raw.alt as f64).abs();
+
+            let mut track = raw.clone();
+            if moved_m >= self.position_tolerance_m || climbed_ft >= self.alt_tolerance_ft {
+                track.lat = lat;
+                track.lon = lon;
+                track.alt = alt.round().max(0.0) as u32;
+                flags[i] = PointFlag::Smoothed;
+            } else {
+                flags[i] = PointFlag::Kept;
+            }
+            tracks.push(track);
+        }
+
+        SmoothedFlight {
+            flight: Flight {
+                id: flight.id.clone(),
+                tracks,
+            },
+            flags,
+        }
+    }
+
+    /// Whether the motion from `a` to `b` is within the model limits
+    fn plausible(&self, a: (DateTime<Utc>, &Track), b: (DateTime<Utc>, &Track)) -> bool {
+        let seconds = (b.0 - a.0).num_milliseconds() as f64 / 1000.0;
+        if seconds <= 0.0 {
+            return false;
+        }
+        let (a, b) = (a.1, b.1);
+
+        let speed_kt = geo::haversine_nm(a.lat, a.lon, b.lat, b.lon) / seconds * 3600.0;
+        let vertical_fpm = (b.alt as f64 - a.alt as f64).abs() / seconds * 60.0;
+        let airborne = !a.on_ground() && !b.on_ground();
+        let turn_rate = geo::heading_difference(a.track as f64, b.track as f64).abs() / seconds;
+
+        speed_kt <= self.max_speed_kt
+            && vertical_fpm <= self.max_vertical_rate_fpm
+            && (!airborne || turn_rate <= self.max_turn_rate_deg_s)
+    }
+
+    /// Time ordered samples that pass the plausibility checks
+    fn accept(
+        &self,
+        tracks: &[Track],
+        samples: &[(DateTime<Utc>, usize)],
+    ) -> Vec<(DateTime<Utc>, usize)> {
+        let point = |s: &(DateTime<Utc>, usize)| (s.0, &tracks[s.1]);
+        let mut accepted: Vec<(DateTime<Utc>, usize)> = Vec::with_capacity(samples.len());
+        let mut rejected_in_row = 0;
+
+        for (n, sample) in samples.iter().enumerate() {
+            let keep = match accepted.last() {
+                Some(last) => {
+                    self.plausible(point(last), point(sample))
+                        || rejected_in_row >= self.max_consecutive_rejections
+                }
+                // An outlier first point would otherwise reject the whole track
+                None => match (samples.get(n + 1), samples.get(n + 2)) {
+                    (Some(next), Some(after)) => {
+                        self.plausible(point(sample), point(next))
+                            || !self.plausible(point(next), point(after))
+                    }
+                    _ => true,
+                },
+            };
+
+            if keep {
+                accepted.push(*sample);
+                rejected_in_row = 0;
+            } else {
+                rejected_in_row += 1;
+            }
+        }
+        accepted
+    }
+
+    /// Smoothed latitude, longitude and altitude of time ordered points
+    fn smooth_points(&self, points: &[(f64, &Track)]) -> Vec<(f64, f64, f64)> {
+        let (lat0, lon0) = (points[0].1.lat, points[0].1.lon);
+        let scale = lat0.to_radians().cos().max(1e-6);
+
+        // Unwrap longitude so tracks crossing the antimeridian stay continuous
+        let mut lon = lon0;
+        let mut previous = lon0;
+        let mut east = Vec::with_capacity(points.len());
+        let mut north = Vec::with_capacity(points.len());
+        let mut alt = Vec::with_capacity(points.len());
+        let mut factors = Vec::with_capacity(points.len());
+        for (_, track) in points {
+            lon += geo::heading_difference(previous, track.lon);
+            previous = track.lon;
+            east.push((lon - lon0) * scale * METRES_PER_DEGREE);
+            north.push((track.lat - lat0) * METRES_PER_DEGREE);
+            alt.push(track.alt as f64);
+            factors.push(source_noise_factor(&track.source));
+        }
+
+        let times: Vec<f64> = points.iter().map(|p| p.0).collect();
+        let sigmas = |base: f64| -> Vec<f64> { factors.iter().map(|f| base * f).collect() };
+        let position_sigmas = sigmas(self.position_sigma_m);
+        let east = rts_smooth(&times, &east, &position_sigmas, self.horizontal_accel_ms2);
+        let north = rts_smooth(&times, &north, &position_sigmas, self.horizontal_accel_ms2);
+        let alt = rts_smooth(
+            &times,
+            &alt,
+            &sigmas(self.alt_sigma_ft),
+            self.vertical_accel_fps2,
+        );
+
+        east.into_iter()
+            .zip(north)
+            .zip(alt)
+            .map(|((e, n), a)| {
+                let lat = lat0 + n / METRES_PER_DEGREE;
+                let lon = lon0 + e / (scale * METRES_PER_DEGREE);
+                (lat, (lon + 180.0).rem_euclid(360.0) - 180.0, a)
+            })
+            .collect()
+    }
+}
+
+type Vector = [f64; 2];
+type Matrix = [[f64; 2]; 2];
+
+/// Constant velocity Kalman filter with a Rauch-Tung-Striebel backward pass
+/// over one axis
+fn rts_smooth(times: &[f64], values: &[f64], sigmas: &[f64], accel: f64) -> Vec<f64> {
+    let n = values.len();
+    let q = accel * accel;
+    let mut filtered: Vec<(Vector, Matrix)> = Vec::with_capacity(n);
+    let mut predicted: Vec<(Vector, Matrix)> = Vec::with_capacity(n);
+
+    // Velocity starts unknown, a wide prior lets the first fixes settle it
+    let velocity_var = (1000.0 * accel).powi(2);
+    let mut x: Vector = [values[0], 0.0];
+    let mut p: Matrix = [[sigmas[0] * sigmas[0], 0.0], [0.0, velocity_var]];
+    for k in 0..n {
+        if k > 0 {
+            let dt = times[k] - times[k - 1];
+            let f = [[1.0, dt], [0.0, 1.0]];
+            let process = [
+                [q * dt.powi(3) / 3.0, q * dt * dt / 2.0],
+                [q * dt * dt / 2.0, q * dt],
+            ];
+            x = [x[0] + dt * x[1], x[1]];
+            p = add(&mul(&mul(&f, &p), &transpose(&f)), &process);
+        }
+        predicted.push((x, p));
+
+        // Position only measurement
+        let r = sigmas[k] * sigmas[k];
+        let s = p[0][0] + r;
+        let gain = [p[0][0] / s, p[1][0] / s];
+        let residual = values[k] - x[0];
+        x = [x[0] + gain[0] * residual, x[1] + gain[1] * residual];
+        p = [
+            [(1.0 - gain[0]) * p[0][0], (1.0 - gain[0]) * p[0][1]],
+            [p[1][0] - gain[1] * p[0][0], p[1][1] - gain[1] * p[0][1]],
+        ];
+        filtered.push((x, p));
+    }
+
+    let mut smoothed = vec![filtered[n - 1].0; n];
+    for k in (0..n - 1).rev() {
+        let (xf, pf) = filtered[k];
+        let (xp, pp) = predicted[k + 1];
+        let dt = times[k + 1] - times[k];
+        let f = [[1.0, dt], [0.0, 1.0]];
+        let Some(pp_inv) = inverse(&pp) else {
+            smoothed[k] = xf;
+            continue;
+        };
+        let c = mul(&mul(&pf, &transpose(&f)), &pp_inv);
+        let diff = [smoothed[k + 1][0] - xp[0], smoothed[k + 1][1] - xp[1]];
+        smoothed[k] = [
+            xf[0] + c[0][0] * diff[0] + c[0][1] * diff[1],
+            xf[1] + c[1][0] * diff[0] + c[1][1] * diff[1],
+        ];
+    }
+    smoothed.into_iter().map(|s| s[0]).collect()
+}
+
+fn mul(a: &Matrix, b: &Matrix) -> Matrix {
+    let mut out = [[0.0; 2]; 2];
+    for (i, row) in out.iter_mut().enumerate() {
+        for (j, cell) in row.iter_mut().enumerate() {
+            *cell = a[i][0] * b[0][j] + a[i][1] * b[1][j];
+        }
+    }
+    out
+}
+
+fn add(a: &Matrix, b: &Matrix) -> Matrix {
+    [
+        [a[0][0] + b[0][0], a[0][1] + b[0][1]],
+        [a[1][0] + b[1][0], a[1][1] + b[1][1]],
+    ]
+}
+
+fn transpose(a: &Matrix) -> Matrix {
+    [[a[0][0], a[1][0]], [a[0][1], a[1][1]]]
+}
+
+fn inverse(a: &Matrix) -> Option<Matrix> {
+    let det = a[0][0] * a[1][1] - a[0][1] * a[1][0];
+    if det.abs() < f64::EPSILON {
+        return None;
+    }
+    Some([
+        [a[1][1] / det, -a[0][1] / det],
+        [-a[1][0] / det, a[0][0] / det],
+    ])
+}
+
+impl Flight {
+    /// Rejects impossible jumps and smooths the tracks with the default
+    /// `SmoothingModel`.
+    /// # Returns
+    ///   A `SmoothedFlight` with one flag per input point
+    pub fn smooth(&self) -> SmoothedFlight {
+        SmoothingModel::default().smooth(self)
+    }
+}
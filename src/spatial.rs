@@ -0,0 +1,231 @@
+/// Spatially indexed queries over live flight snapshots
+use crate::client::{
+    Bounds, FlightRadarClient, FullLiveFlightData, FullLiveFlightQuery, FullLiveFlightResponse,
+};
+use crate::error::FlightRadarError;
+use crate::geo;
+use rstar::{primitives::GeomWithData, RTree};
+
+type IndexedPosition = GeomWithData<[f64; 3], usize>;
+
+/// R-tree over the positions of a `flight-positions` snapshot
+pub struct SnapshotIndex<'a> {
+    flights: &'a [FullLiveFlightData],
+    tree: RTree<IndexedPosition>,
+}
+
+impl<'a> SnapshotIndex<'a> {
+    /// Builds the index.
+    /// # Arguments
+    ///   * `flights` - Aircraft states from the `flight-positions` endpoint
+    /// # Returns
+    ///   A `SnapshotIndex` borrowing the flights
+    pub fn new(flights: &'a [FullLiveFlightData]) -> Self {
+        let tree = RTree::bulk_load(
+            flights
+                .iter()
+                .enumerate()
+                .map(|(i, f)| GeomWithData::new(geo::to_cartesian_nm(f.lat, f.lon), i))
+                .collect(),
+        );
+        SnapshotIndex { flights, tree }
+    }
+
+    /// Number of indexed aircraft
+    pub fn len(&self) -> usize {
+        self.flights.len()
+    }
+
+    /// Whether the snapshot is empty
+    pub fn is_empty(&self) -> bool {
+        self.flights.is_empty()
+    }
+
+    /// Closest aircraft to a position.
+    /// # Arguments
+    ///   * `lat`, `lon` - Position in degrees
+    ///   * `k` - Maximum number of aircraft returned
+    /// # Returns
+    ///   Aircraft with their great-circle distance in nautical miles, closest first
+    pub fn nearest(&self, lat: f64, lon: f64, k: usize) -> Vec<(&'a FullLiveFlightData, f64)> {
+        // Chord length grows with great-circle distance, so the tree order holds
+        self.tree
+            .nearest_neighbor_iter(&geo::to_cartesian_nm(lat, lon))
+            .take(k)
+            .map(|p| self.with_distance(p.data, lat, lon))
+            .collect()
+    }
+
+    /// Aircraft within a great-circle radius of a position.
+    /// # Arguments
+    ///   * `lat`, `lon` - Position in degrees
+    ///   * `radius_nm` - Radius in nautical miles
+    /// # Returns
+    ///   Aircraft with their distance in nautical miles, closest first
+    pub fn within_radius(
+        &self,
+        lat: f64,
+        lon: f64,
+        radius_nm: f64,
+    ) -> Vec<(&'a FullLiveFlightData, f64)> {
+        let mut found: Vec<(&'a FullLiveFlightData, f64)> = self
+            .tree
+            .locate_within_distance(geo::to_cartesian_nm(lat, lon), radius_nm * radius_nm)
+            .map(|p| self.with_distance(p.data, lat, lon))
+            .filter(|(_, distance)| *distance <= radius_nm)
+            .collect();
+        found.sort_by(|a, b| a.1.total_cmp(&b.1));
+        found
+    }
+
+    /// Aircraft inside a corridor around a route.
+    /// # Arguments
+    ///   * `route` - Route as `(lat, lon)` points in degrees
+    ///   * `corridor_nm` - Half width of the corridor in nautical miles
+    /// # Returns
+    ///   Aircraft with their distance from the route in nautical miles, ordered
+    ///   by progress along the route
+    pub fn along_route(
+        &self,
+        route: &[(f64, f64)],
+        corridor_nm: f64,
+    ) -> Vec<(&'a FullLiveFlightData, f64)> {
+        if route.len() < 2 {
+            return match route.first() {
+                Some(&(lat, lon)) => self.within_radius(lat, lon, corridor_nm),
+                None => Vec::new(),
+            };
+        }
+
+        // Candidates near any segment, then the exact distance over the whole route
+        let mut candidates = vec![false; self.flights.len()];
+        for segment in route.windows(2) {
+            let ((lat1, lon1), (lat2, lon2)) = (segment[0], segment[1]);
+            let (mid_lat, mid_lon) = geo::interpolate_great_circle(lat1, lon1, lat2, lon2, 0.5);
+            let reach = geo::haversine_nm(lat1, lon1, lat2, lon2) / 2.0 + corridor_nm;
+            for p in self
+                .tree
+                .locate_within_distance(geo::to_cartesian_nm(mid_lat, mid_lon), reach * reach)
+            {
+                candidates[p.data] = true;
+            }
+        }
+
+        let mut found: Vec<(&'a FullLiveFlightData, f64, f64)> = Vec::new();
+        for (i, _) in candidates.iter().enumerate().filter(|(_, c)| **c) {
+            let flight = &self.flights[i];
+            let mut best: Option<(f64, f64)> = None;
+            let mut travelled = 0.0;
+            for segment in route.windows(2) {
+                let ((lat1, lon1), (lat2, lon2)) = (segment[0], segment[1]);
+                let (distance, along) =
+                    geo::segment_distance_nm(flight.lat, flight.lon, lat1, lon1, lat2, lon2);
+                if best.is_none_or(|(d, _)| distance < d) {
+                    best = Some((distance, travelled + along));
+                }
+                travelled += geo::haversine_nm(lat1, lon1, lat2, lon2);
+            }
+
+            if let Some((distance, progress)) = best.filter(|(d, _)| *d <= corridor_nm) {
+                found.push((flight, distance, progress));
+            }
+        }
+
+        found.sort_by(|a, b| a.2.total_cmp(&b.2));
+        found.into_iter().map(|(f, d, _)| (f, d)).collect()
+    }
+
+    fn with_distance(&self, index: usize, lat: f64, lon: f64) -> (&'a FullLiveFlightData, f64) {
+        let flight = &self.flights[index];
+        (flight, geo::haversine_nm(lat, lon, flight.lat, flight.lon))
+    }
+}
+
+impl FullLiveFlightResponse {
+    /// Spatial index over the snapshot's aircraft
+    pub fn spatial_index(&self) -> SnapshotIndex<'_> {
+        SnapshotIndex::new(&self.data)
+    }
+}
+
+impl Bounds {
+    /// Smallest bounding box containing a circle.
+    ///
+    /// Circles reaching a pole or crossing the antimeridian span every longitude.
+    /// # Arguments
+    ///   * `lat`, `lon` - Centre in degrees
+    ///   * `radius_nm` - Radius in nautical miles
+    /// # Returns
+    ///   The enclosing `Bounds`
+    pub fn around(lat: f64, lon: f64, radius_nm: f64) -> Self {
+        let angle = (radius_nm / geo::EARTH_RADIUS_NM).to_degrees();
+        let north = lat + angle;
+        let south = lat - angle;
+
+        // Widest longitude reached on a sphere, at the circle's tangent points
+        let half_width = (radius_nm / geo::EARTH_RADIUS_NM).sin() / lat.to_radians().cos();
+        let (west, east) = if north >= 90.0 || south <= -90.0 || half_width >= 1.0 {
+            (-180.0, 180.0)
+        } else {
+            let half_width = half_width.asin().to_degrees();
+            if lon - half_width < -180.0 || lon + half_width > 180.0 {
+                (-180.0, 180.0)
+            } else {
+                (lon - half_width, lon + half_width)
+            }
+        };
+
+        Bounds {
+            north: north.min(90.0),
+            south: south.max(-90.0),
+            west,
+            east,
+        }
+    }
+}
+
+impl FlightRadarClient {
+    /// Fetches live flights within a radius of a position.
+    ///
+    /// Requests the smallest `Bounds` around the circle, then drops the aircraft
+    /// in its corners.
+    /// # Arguments
+    ///   * `lat`, `lon` - Centre in degrees
+    ///   * `radius_nm` - Radius in nautical miles
+    ///   * `other_queries` - Optional parameters to narrow down data, any bounds are replaced
+    /// # Returns
+    ///   A `FullLiveFlightResponse` ordered by distance on success or a `FlightRadarError` on failure.
+    pub fn get_live_flight_within_radius(
+        &self,
+        lat: f64,
+        lon: f64,
+        radius_nm: f64,
+        other_queries: Option<&FullLiveFlightQuery>,
+    ) -> Result<FullLiveFlightResponse, FlightRadarError> {
+        if radius_nm.is_nan()
+            || radius_nm <= 0.0
+            || !(-90.0..=90.0).contains(&lat)
+            || !(-180.0..=180.0).contains(&lon)
+        {
+            return Err(FlightRadarError::Parameter(format!(
+                "Radius Query: {},{} {}nm",
+                lat, lon, radius_nm
+            )));
+        }
+
+        let mut query = other_queries.cloned().unwrap_or_default();
+        query.bounds = Some(Bounds::around(lat, lon, radius_nm));
+        let mut response = self.get_live_flight(Some(&query))?;
+
+        let mut data: Vec<(f64, FullLiveFlightData)> = response
+            .data
+            .drain(..)
+            .map(|f| (geo::haversine_nm(lat, lon, f.lat, f.lon), f))
+            .filter(|(distance, _)| *distance <= radius_nm)
+            .collect();
+        data.sort_by(|a, b| a.0.total_cmp(&b.0));
+        response.data = data.into_iter().map(|(_, f)| f).collect();
+
+        Ok(response)
+    }
+}
@@ -0,0 +1,619 @@
+/// Local SQLite archive of snapshots, tracks and static lookups
+use crate::client::{
+    Airline, Airport, Bounds, Country, Flight, FlightRadarClient, FullLiveFlightQuery,
+    FullLiveFlightResponse, LightLiveFlightResponse, Timezone, Track,
+};
+use crate::error::FlightRadarError;
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use std::path::Path;
+
+/// Schema migrations, applied in order and tracked with `PRAGMA user_version`
+const MIGRATIONS: &[&str] = &[
+    r#"
+CREATE TABLE flights (
+    fr24_id TEXT PRIMARY KEY,
+    flight TEXT,
+    callsign TEXT,
+    hex TEXT,
+    type TEXT,
+    reg TEXT,
+    painted_as TEXT,
+    operating_as TEXT,
+    orig_iata TEXT,
+    orig_icao TEXT,
+    dest_iata TEXT,
+    dest_icao TEXT,
+    eta TEXT
+);
+CREATE TABLE positions (
+    fr24_id TEXT NOT NULL REFERENCES flights(fr24_id),
+    timestamp INTEGER NOT NULL,
+    lat REAL NOT NULL,
+    lon REAL NOT NULL,
+    track INTEGER NOT NULL,
+    alt INTEGER NOT NULL,
+    gspeed INTEGER NOT NULL,
+    vspeed INTEGER NOT NULL,
+    squawk TEXT NOT NULL,
+    callsign TEXT NOT NULL,
+    source TEXT NOT NULL,
+    PRIMARY KEY (fr24_id, timestamp)
+);
+CREATE INDEX positions_timestamp ON positions(timestamp);
+CREATE TABLE track_points (
+    fr24_id TEXT NOT NULL REFERENCES flights(fr24_id),
+    timestamp INTEGER NOT NULL,
+    lat REAL NOT NULL,
+    lon REAL NOT NULL,
+    track INTEGER NOT NULL,
+    alt INTEGER NOT NULL,
+    gspeed INTEGER NOT NULL,
+    vspeed INTEGER NOT NULL,
+    squawk TEXT NOT NULL,
+    callsign TEXT NOT NULL,
+    source TEXT NOT NULL,
+    PRIMARY KEY (fr24_id, timestamp)
+);
+CREATE TABLE airports (
+    icao TEXT PRIMARY KEY,
+    iata TEXT,
+    name TEXT NOT NULL,
+    lat REAL NOT NULL,
+    lon REAL NOT NULL,
+    elevation INTEGER NOT NULL,
+    country_code TEXT NOT NULL,
+    country_name TEXT NOT NULL,
+    city TEXT NOT NULL,
+    state TEXT,
+    timezone_name TEXT NOT NULL,
+    timezone_offset INTEGER NOT NULL
+);
+CREATE INDEX airports_iata ON airports(iata);
+CREATE TABLE airlines (
+    icao TEXT PRIMARY KEY,
+    iata TEXT,
+    name TEXT NOT NULL
+);
+"#,
+    r#"
+CREATE INDEX track_points_timestamp ON track_points(timestamp);
+"#,
+];
+
+/// Position of a flight as stored from a live or historic snapshot
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredPosition {
+    pub fr24_id: String,
+    pub timestamp: DateTime<Utc>,
+    pub lat: f64,
+    pub lon: f64,
+    pub track: u32,
+    pub alt: u32,
+    pub gspeed: u32,
+    pub vspeed: i32,
+    pub squawk: String,
+    pub callsign: String,
+    pub source: String,
+}
+
+/// SQLite backed archive of API results
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    /// Opens (or creates) an archive file and applies pending migrations.
+    /// # Arguments
+    ///   * `path` - Location of the SQLite database file
+    /// # Returns
+    ///   A `Store` on success or a `FlightRadarError` on failure.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, FlightRadarError> {
+        Self::from_connection(Connection::open(path).map_err(store_error)?)
+    }
+
+    /// Opens a temporary in-memory archive.
+    /// # Returns
+    ///   A `Store` on success or a `FlightRadarError` on failure.
+    pub fn open_in_memory() -> Result<Self, FlightRadarError> {
+        Self::from_connection(Connection::open_in_memory().map_err(store_error)?)
+    }
+
+    fn from_connection(conn: Connection) -> Result<Self, FlightRadarError> {
+        let store = Store { conn };
+        store.migrate()?;
+        Ok(store)
+    }
+
+    fn migrate(&self) -> Result<(), FlightRadarError> {
+        let version: usize = self
+            .conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .map_err(store_error)?;
+
+        for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+            let tx = self.conn.unchecked_transaction().map_err(store_error)?;
+            tx.execute_batch(migration).map_err(store_error)?;
+            tx.pragma_update(None, "user_version", index + 1)
+                .map_err(store_error)?;
+            tx.commit().map_err(store_error)?;
+        }
+
+        Ok(())
+    }
+
+    /// Archives every flight and position of a full snapshot.
+    /// # Arguments
+    ///   * `response` - Full live or historic snapshot
+    /// # Returns
+    ///   Number of positions stored on success or a `FlightRadarError` on failure.
+    pub fn insert_full_live(
+        &self,
+        response: &FullLiveFlightResponse,
+    ) -> Result<usize, FlightRadarError> {
+        let tx = self.conn.unchecked_transaction().map_err(store_error)?;
+        for data in response.data.iter() {
+            tx.execute(
+                "INSERT INTO flights (fr24_id, flight, callsign, hex, type, reg, painted_as,
+                     operating_as, orig_iata, orig_icao, dest_iata, dest_icao, eta)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
+                 ON CONFLICT(fr24_id) DO UPDATE SET flight = excluded.flight,
+                     callsign = excluded.callsign, hex = excluded.hex, type = excluded.type,
+                     reg = excluded.reg, painted_as = excluded.painted_as,
+                     operating_as = excluded.operating_as, orig_iata = excluded.orig_iata,
+                     orig_icao = excluded.orig_icao, dest_iata = excluded.dest_iata,
+                     dest_icao = excluded.dest_icao, eta = excluded.eta",
+                params![
+                    data.fr24_id,
+                    data.flight,
+                    data.callsign,
+                    data.hex,
+                    data.type_field,
+                    data.reg,
+                    data.painted_as,
+                    data.operating_as,
+                    data.orig_iata,
+                    data.orig_icao,
+                    data.dest_iata,
+                    data.dest_icao,
+                    data.eta
+                ],
+            )
+            .map_err(store_error)?;
+            tx.execute(
+                "INSERT OR REPLACE INTO positions VALUES
+                     (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                params![
+                    data.fr24_id,
+                    parse_millis(&data.timestamp)?,
+                    data.lat,
+                    data.lon,
+                    data.track,
+                    data.alt,
+                    data.gspeed,
+                    data.vspeed,
+                    data.squawk,
+                    data.callsign,
+                    data.source
+                ],
+            )
+            .map_err(store_error)?;
+        }
+        tx.commit().map_err(store_error)?;
+
+        Ok(response.data.len())
+    }
+
+    /// Archives every flight and position of a light snapshot.
+    /// # Arguments
+    ///   * `response` - Light live or historic snapshot
+    /// # Returns
+    ///   Number of positions stored on success or a `FlightRadarError` on failure.
+    pub fn insert_light_live(
+        &self,
+        response: &LightLiveFlightResponse,
+    ) -> Result<usize, FlightRadarError> {
+        let tx = self.conn.unchecked_transaction().map_err(store_error)?;
+        for data in response.data.iter() {
+            tx.execute(
+                "INSERT INTO flights (fr24_id, callsign, hex) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(fr24_id) DO UPDATE SET callsign = excluded.callsign,
+                     hex = excluded.hex",
+                params![data.fr24_id, data.callsign, data.hex],
+            )
+            .map_err(store_error)?;
+            tx.execute(
+                "INSERT OR REPLACE INTO positions VALUES
+                     (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                params![
+                    data.fr24_id,
+                    parse_millis(&data.timestamp)?,
+                    data.lat,
+                    data.lon,
+                    data.track,
+                    data.alt,
+                    data.gspeed,
+                    data.vspeed,
+                    data.squawk,
+                    data.callsign,
+                    data.source
+                ],
+            )
+            .map_err(store_error)?;
+        }
+        tx.commit().map_err(store_error)?;
+
+        Ok(response.data.len())
+    }
+
+    /// Archives the tracks returned from the `flight-tracks` endpoint.
+    /// # Arguments
+    ///   * `flights` - Flights with their tracks
+    /// # Returns
+    ///   Number of track points stored on success or a `FlightRadarError` on failure.
+    pub fn insert_flights(&self, flights: &[Flight]) -> Result<usize, FlightRadarError> {
+        let mut count = 0;
+        let tx = self.conn.unchecked_transaction().map_err(store_error)?;
+        for flight in flights.iter() {
+            tx.execute(
+                "INSERT OR IGNORE INTO flights (fr24_id) VALUES (?1)",
+                params![flight.id],
+            )
+            .map_err(store_error)?;
+            for track in flight.tracks.iter() {
+                tx.execute(
+                    "INSERT OR REPLACE INTO track_points VALUES
+                         (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                    params![
+                        flight.id,
+                        parse_millis(&track.timestamp)?,
+                        track.lat,
+                        track.lon,
+                        track.track,
+                        track.alt,
+                        track.gspeed,
+                        track.vspeed,
+                        track.squawk,
+                        track.callsign,
+                        track.source
+                    ],
+                )
+                .map_err(store_error)?;
+                count += 1;
+            }
+        }
+        tx.commit().map_err(store_error)?;
+
+        Ok(count)
+    }
+
+    /// Archives airport details.
+    pub fn insert_airport(&self, airport: &Airport) -> Result<(), FlightRadarError> {
+        self.conn
+            .execute(
+                "INSERT OR REPLACE INTO airports VALUES
+                     (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                params![
+                    airport.icao,
+                    airport.iata,
+                    airport.name,
+                    airport.lat,
+                    airport.lon,
+                    airport.elevation,
+                    airport.country.code,
+                    airport.country.name,
+                    airport.city,
+                    airport.state,
+                    airport.timezone.name,
+                    airport.timezone.offset
+                ],
+            )
+            .map_err(store_error)?;
+        Ok(())
+    }
+
+    /// Archives airline details.
+    pub fn insert_airline(&self, airline: &Airline) -> Result<(), FlightRadarError> {
+        self.conn
+            .execute(
+                "INSERT OR REPLACE INTO airlines VALUES (?1, ?2, ?3)",
+                params![airline.icao, airline.iata, airline.name],
+            )
+            .map_err(store_error)?;
+        Ok(())
+    }
+
+    /// Snapshot positions and archived track points of a flight, oldest first.
+    /// A snapshot position replaces a track point with the same timestamp.
+    /// # Arguments
+    ///   * `fr24_id` - Flight identifier
+    ///   * `from`, `to` - Inclusive time range
+    /// # Returns
+    ///   A `Vec<StoredPosition>` on success or a `FlightRadarError` on failure.
+    pub fn positions(
+        &self,
+        fr24_id: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<StoredPosition>, FlightRadarError> {
+        let mut statement = self
+            .conn
+            .prepare(
+                "SELECT * FROM positions WHERE fr24_id = ?1 AND timestamp BETWEEN ?2 AND ?3
+                 UNION ALL
+                 SELECT * FROM track_points AS t
+                 WHERE fr24_id = ?1 AND timestamp BETWEEN ?2 AND ?3
+                     AND NOT EXISTS (SELECT 1 FROM positions AS p
+                         WHERE p.fr24_id = t.fr24_id AND p.timestamp = t.timestamp)
+                 ORDER BY timestamp",
+            )
+            .map_err(store_error)?;
+        let rows = statement
+            .query_map(
+                params![fr24_id, from.timestamp_millis(), to.timestamp_millis()],
+                position_from_row,
+            )
+            .map_err(store_error)?;
+
+        rows.collect::<Result<Vec<_>, _>>().map_err(store_error)
+    }
+
+    /// Flights with at least one snapshot position or archived track point inside the bounds.
+    /// # Arguments
+    ///   * `bounds` - Area to search, `west > east` crosses the antimeridian
+    ///   * `from`, `to` - Inclusive time range
+    /// # Returns
+    ///   A `Vec<String>` of fr24_ids on success or a `FlightRadarError` on failure.
+    pub fn flights_in_bounds(
+        &self,
+        bounds: &Bounds,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<String>, FlightRadarError> {
+        let mut statement = self
+            .conn
+            .prepare(
+                "SELECT fr24_id FROM positions
+                 WHERE timestamp BETWEEN ?1 AND ?2 AND lat BETWEEN ?3 AND ?4
+                     AND ((?5 <= ?6 AND lon BETWEEN ?5 AND ?6)
+                         OR (?5 > ?6 AND (lon >= ?5 OR lon <= ?6)))
+                 UNION
+                 SELECT fr24_id FROM track_points
+                 WHERE timestamp BETWEEN ?1 AND ?2 AND lat BETWEEN ?3 AND ?4
+                     AND ((?5 <= ?6 AND lon BETWEEN ?5 AND ?6)
+                         OR (?5 > ?6 AND (lon >= ?5 OR lon <= ?6)))
+                 ORDER BY fr24_id",
+            )
+            .map_err(store_error)?;
+        let rows = statement
+            .query_map(
+                params![
+                    from.timestamp_millis(),
+                    to.timestamp_millis(),
+                    bounds.south,
+                    bounds.north,
+                    bounds.west,
+                    bounds.east
+                ],
+                |row| row.get(0),
+            )
+            .map_err(store_error)?;
+
+        rows.collect::<Result<Vec<_>, _>>().map_err(store_error)
+    }
+
+    /// Archived `flight-tracks` result for a flight.
+    /// # Returns
+    ///   `Some(Flight)` when tracks are archived, `None` otherwise, or a `FlightRadarError` on failure.
+    pub fn flight_tracks(&self, fr24_id: &str) -> Result<Option<Flight>, FlightRadarError> {
+        let mut statement = self
+            .conn
+            .prepare("SELECT * FROM track_points WHERE fr24_id = ?1 ORDER BY timestamp")
+            .map_err(store_error)?;
+        let rows = statement
+            .query_map(params![fr24_id], |row| {
+                let position = position_from_row(row)?;
+                Ok(Track {
+                    timestamp: position
+                        .timestamp
+                        .to_rfc3339_opts(SecondsFormat::Secs, true),
+                    lat: position.lat,
+                    lon: position.lon,
+                    alt: position.alt,
+                    gspeed: position.gspeed,
+                    vspeed: position.vspeed,
+                    track: position.track,
+                    squawk: position.squawk,
+                    callsign: position.callsign,
+                    source: position.source,
+                })
+            })
+            .map_err(store_error)?;
+        let tracks = rows.collect::<Result<Vec<_>, _>>().map_err(store_error)?;
+
+        if tracks.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Flight {
+            id: fr24_id.to_string(),
+            tracks,
+        }))
+    }
+
+    /// Archived airport by IATA or ICAO code.
+    pub fn airport(&self, code: &str) -> Result<Option<Airport>, FlightRadarError> {
+        self.conn
+            .query_row(
+                "SELECT * FROM airports WHERE icao = ?1 OR iata = ?1 LIMIT 1",
+                params![code],
+                |row| {
+                    Ok(Airport {
+                        name: row.get("name")?,
+                        iata: row.get("iata")?,
+                        icao: row.get("icao")?,
+                        lon: row.get("lon")?,
+                        lat: row.get("lat")?,
+                        elevation: row.get("elevation")?,
+                        country: Country {
+                            code: row.get("country_code")?,
+                            name: row.get("country_name")?,
+                        },
+                        city: row.get("city")?,
+                        state: row.get("state")?,
+                        timezone: Timezone {
+                            name: row.get("timezone_name")?,
+                            offset: row.get("timezone_offset")?,
+                        },
+                    })
+                },
+            )
+            .optional()
+            .map_err(store_error)
+    }
+
+    /// Archived airline by ICAO code.
+    pub fn airline(&self, icao: &str) -> Result<Option<Airline>, FlightRadarError> {
+        self.conn
+            .query_row(
+                "SELECT * FROM airlines WHERE icao = ?1",
+                params![icao],
+                |row| {
+                    Ok(Airline {
+                        name: row.get("name")?,
+                        iata: row.get("iata")?,
+                        icao: row.get("icao")?,
+                    })
+                },
+            )
+            .optional()
+            .map_err(store_error)
+    }
+}
+
+/// Client wrapper that archives everything it fetches into a `Store`
+pub struct ArchivingClient {
+    client: FlightRadarClient,
+    store: Store,
+}
+
+impl ArchivingClient {
+    /// Creates a new archiving client.
+    /// # Arguments
+    ///   * `client` - Client used for API requests
+    ///   * `store` - Archive that receives every result
+    pub fn new(client: FlightRadarClient, store: Store) -> Self {
+        ArchivingClient { client, store }
+    }
+
+    /// Underlying API client
+    pub fn client(&self) -> &FlightRadarClient {
+        &self.client
+    }
+
+    /// Underlying archive
+    pub fn store(&self) -> &Store {
+        &self.store
+    }
+
+    /// `FlightRadarClient::get_live_flight`, archiving the snapshot
+    pub fn get_live_flight(
+        &self,
+        other_queries: Option<&FullLiveFlightQuery>,
+    ) -> Result<FullLiveFlightResponse, FlightRadarError> {
+        let response = self.client.get_live_flight(other_queries)?;
+        self.store.insert_full_live(&response)?;
+        Ok(response)
+    }
+
+    /// `FlightRadarClient::get_live_flight_light`, archiving the snapshot
+    pub fn get_live_flight_light(
+        &self,
+        other_queries: Option<&FullLiveFlightQuery>,
+    ) -> Result<LightLiveFlightResponse, FlightRadarError> {
+        let response = self.client.get_live_flight_light(other_queries)?;
+        self.store.insert_light_live(&response)?;
+        Ok(response)
+    }
+
+    /// `FlightRadarClient::get_historic_flight`, archiving the snapshot
+    pub fn get_historic_flight(
+        &self,
+        timestamp: &u64,
+        other_queries: Option<&FullLiveFlightQuery>,
+    ) -> Result<FullLiveFlightResponse, FlightRadarError> {
+        let response = self.client.get_historic_flight(timestamp, other_queries)?;
+        self.store.insert_full_live(&response)?;
+        Ok(response)
+    }
+
+    /// `FlightRadarClient::get_historic_flight_light`, archiving the snapshot
+    pub fn get_historic_flight_light(
+        &self,
+        timestamp: &u64,
+        other_queries: Option<&FullLiveFlightQuery>,
+    ) -> Result<LightLiveFlightResponse, FlightRadarError> {
+        let response = self
+            .client
+            .get_historic_flight_light(timestamp, other_queries)?;
+        self.store.insert_light_live(&response)?;
+        Ok(response)
+    }
+
+    /// `FlightRadarClient::get_flight_tracks_by_id`, archiving the tracks
+    pub fn get_flight_tracks_by_id(
+        &self,
+        flight_id: &str,
+    ) -> Result<Vec<Flight>, FlightRadarError> {
+        let flights = self.client.get_flight_tracks_by_id(flight_id)?;
+        self.store.insert_flights(&flights)?;
+        Ok(flights)
+    }
+
+    /// `FlightRadarClient::get_airport_by_code`, served from the archive when present
+    pub fn get_airport_by_code(&self, code: &str) -> Result<Airport, FlightRadarError> {
+        if let Some(airport) = self.store.airport(code)? {
+            return Ok(airport);
+        }
+        let airport = self.client.get_airport_by_code(code)?;
+        self.store.insert_airport(&airport)?;
+        Ok(airport)
+    }
+
+    /// `FlightRadarClient::get_airline_by_icao`, served from the archive when present
+    pub fn get_airline_by_icao(&self, icao: &str) -> Result<Airline, FlightRadarError> {
+        if let Some(airline) = self.store.airline(icao)? {
+            return Ok(airline);
+        }
+        let airline = self.client.get_airline_by_icao(icao)?;
+        self.store.insert_airline(&airline)?;
+        Ok(airline)
+    }
+}
+
+fn position_from_row(row: &Row) -> rusqlite::Result<StoredPosition> {
+    let millis: i64 = row.get("timestamp")?;
+    Ok(StoredPosition {
+        fr24_id: row.get("fr24_id")?,
+        timestamp: DateTime::from_timestamp_millis(millis).unwrap_or_default(),
+        lat: row.get("lat")?,
+        lon: row.get("lon")?,
+        track: row.get("track")?,
+        alt: row.get("alt")?,
+        gspeed: row.get("gspeed")?,
+        vspeed: row.get("vspeed")?,
+        squawk: row.get("squawk")?,
+        callsign: row.get("callsign")?,
+        source: row.get("source")?,
+    })
+}
+
+fn parse_millis(timestamp: &str) -> Result<i64, FlightRadarError> {
+    timestamp
+        .parse::<DateTime<Utc>>()
+        .map(|t| t.timestamp_millis())
+        .map_err(|e| FlightRadarError::Parsing(format!("Timestamp: {} ({})", timestamp, e)))
+}
+
+fn store_error(err: rusqlite::Error) -> FlightRadarError {
+    FlightRadarError::General(format!("Store: {}", err))
+}
@@ -0,0 +1,254 @@
+/// Time series of historic snapshots with credit budget and checkpoints
+use crate::client::{
+    FlightRadarClient, FullLiveFlightQuery, FullLiveFlightResponse, MIN_TIMESTAMP,
+};
+use crate::error::FlightRadarError;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Progress of a sweep, enough to resume it after an interruption
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SweepCheckpoint {
+    pub from: u64,
+    pub to: u64,
+    /// Seconds between snapshots
+    pub step: u64,
+    /// Timestamp of the next snapshot to fetch
+    pub next: u64,
+    /// Credits spent so far, one per returned aircraft
+    pub credits_used: u64,
+    /// Filters of the sweep as URL parameters, a resumed sweep must match them
+    pub query: String,
+}
+
+impl SweepCheckpoint {
+    /// Reads a checkpoint file.
+    /// # Arguments
+    ///   * `path` - Location of the checkpoint JSON file
+    /// # Returns
+    ///   A `SweepCheckpoint` on success or a `FlightRadarError` on failure.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, FlightRadarError> {
+        let text = fs::read_to_string(path)?;
+        serde_json::from_str(&text)
+            .map_err(|e| FlightRadarError::Parsing(format!("Checkpoint: {}", e)))
+    }
+
+    /// Writes the checkpoint as JSON.
+    /// # Arguments
+    ///   * `path` - Location of the checkpoint JSON file
+    /// # Returns
+    ///   `()` on success or a `FlightRadarError` on failure.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), FlightRadarError> {
+        let text = serde_json::to_string(self)
+            .map_err(|e| FlightRadarError::Parsing(format!("Checkpoint: {}", e)))?;
+        fs::write(path, text)?;
+        Ok(())
+    }
+
+    /// Whether every snapshot of the sweep has been fetched
+    pub fn is_complete(&self) -> bool {
+        self.next > self.to
+    }
+}
+
+/// Iterator over historic snapshots from `from` to `to`, in time order.
+///
+/// Each item is the snapshot timestamp with its response. The sweep stops
+/// after the first error; the checkpoint still points at the failed
+/// timestamp so a later run retries it. Pacing comes from the client's rate
+/// limiter, see `FlightRadarClient::set_rate_limit`.
+pub struct HistoricRange<'c> {
+    client: &'c FlightRadarClient,
+    query: FullLiveFlightQuery,
+    checkpoint: SweepCheckpoint,
+    credit_budget: Option<u64>,
+    checkpoint_path: Option<PathBuf>,
+    stopped: bool,
+}
+
+impl<'c> HistoricRange<'c> {
+    /// Limits the credits spent by the sweep, counting those of resumed runs.
+    ///
+    /// Each request's `limit` is lowered to the credits left, so a snapshot
+    /// never returns more aircraft than the budget allows. Reaching the budget
+    /// ends the sweep with an error.
+    pub fn with_credit_budget(mut self, credits: u64) -> Self {
+        self.credit_budget = Some(credits);
+        self
+    }
+
+    /// Saves progress to a file after every snapshot, resuming from it when
+    /// it already exists.
+    /// # Arguments
+    ///   * `path` - Location of the checkpoint JSON file
+    /// # Returns
+    ///   The `HistoricRange` on success or a `FlightRadarError` when the file
+    ///   can't be read or belongs to a different sweep.
+    pub fn with_checkpoint_file<P: Into<PathBuf>>(
+        mut self,
+        path: P,
+    ) -> Result<Self, FlightRadarError> {
+        let path = path.into();
+        if path.exists() {
+            let saved = SweepCheckpoint::load(&path)?;
+            let current = &self.checkpoint;
+            if (saved.from, saved.to, saved.step) != (current.from, current.to, current.step) {
+                return Err(FlightRadarError::Parameter(format!(
+                    "Checkpoint {} is for {}..{} every {}s",
+                    path.display(),
+                    saved.from,
+                    saved.to,
+                    saved.step
+                )));
+            }
+            if saved.query != current.query {
+                return Err(FlightRadarError::Parameter(format!(
+                    "Checkpoint {} is for query {}",
+                    path.display(),
+                    saved.query
+                )));
+            }
+            self.checkpoint = saved;
+        }
+        self.checkpoint_path = Some(path);
+        Ok(self)
+    }
+
+    /// Current progress of the sweep
+    pub fn checkpoint(&self) -> &SweepCheckpoint {
+        &self.checkpoint
+    }
+
+    /// Credits spent so far, one per returned aircraft
+    pub fn credits_used(&self) -> u64 {
+        self.checkpoint.credits_used
+    }
+
+    /// Number of snapshots not fetched yet
+    pub fn remaining(&self) -> u64 {
+        let checkpoint = &self.checkpoint;
+        if checkpoint.is_complete() {
+            0
+        } else {
+            (checkpoint.to - checkpoint.next) / checkpoint.step + 1
+        }
+    }
+
+    /// Query for the next request, its `limit` lowered to the credits left
+    fn budgeted_query(&self) -> Result<FullLiveFlightQuery, FlightRadarError> {
+        let mut query = self.query.clone();
+        let Some(budget) = self.credit_budget else {
+            return Ok(query);
+        };
+        let left = budget.saturating_sub(self.checkpoint.credits_used);
+        if left == 0 {
+            return Err(FlightRadarError::General(format!(
+                "Credit budget exhausted: {} of {} used, stopped at {}",
+                self.checkpoint.credits_used, budget, self.checkpoint.next
+            )));
+        }
+        let left = u32::try_from(left).unwrap_or(u32::MAX);
+        query.limit = Some(query.limit.map_or(left, |limit| limit.min(left)));
+        Ok(query)
+    }
+}
+
+impl Iterator for HistoricRange<'_> {
+    type Item = Result<(u64, FullLiveFlightResponse), FlightRadarError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.stopped || self.checkpoint.is_complete() {
+            return None;
+        }
+        let query = match self.budgeted_query() {
+            Ok(query) => query,
+            Err(e) => {
+                self.stopped = true;
+                return Some(Err(e));
+            }
+        };
+
+        let timestamp = self.checkpoint.next;
+        let response = match self.client.get_historic_flight(&timestamp, Some(&query)) {
+            Ok(response) => response,
+            Err(e) => {
+                self.stopped = true;
+                return Some(Err(e));
+            }
+        };
+
+        self.checkpoint.credits_used += response.data.len() as u64;
+        self.checkpoint.next = timestamp + self.checkpoint.step;
+        if let Some(path) = &self.checkpoint_path {
+            if let Err(e) = self.checkpoint.save(path) {
+                self.stopped = true;
+                return Some(Err(e));
+            }
+        }
+        Some(Ok((timestamp, response)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.stopped {
+            return (0, Some(0));
+        }
+        let remaining = usize::try_from(self.remaining()).ok();
+        (0, remaining)
+    }
+}
+
+impl FlightRadarClient {
+    /// Sweeps historic snapshots at a fixed interval.
+    /// # Arguments
+    ///   * `query` - Filters applied to every snapshot, one historic parameter is required
+    ///   * `from` - First timestamp, not before `MIN_TIMESTAMP`
+    ///   * `to` - Last timestamp, included when it falls on a step
+    ///   * `step` - Seconds between snapshots
+    /// # Returns
+    ///   A `HistoricRange` on success or a `FlightRadarError` on invalid parameters.
+    pub fn historic_range(
+        &self,
+        query: &FullLiveFlightQuery,
+        from: u64,
+        to: u64,
+        step: u64,
+    ) -> Result<HistoricRange<'_>, FlightRadarError> {
+        if from < MIN_TIMESTAMP {
+            return Err(FlightRadarError::Parameter(format!(
+                "Invalid Timestamp: {}",
+                from
+            )));
+        }
+        if to < from {
+            return Err(FlightRadarError::Parameter(format!(
+                "Range: {} is before {}",
+                to, from
+            )));
+        }
+        if step == 0 {
+            return Err(FlightRadarError::Parameter("Step: 0".to_string()));
+        }
+        if !Self::check_historic_parameters(query) {
+            return Err(FlightRadarError::Parameter(
+                "Missing One Of Required Parameters".to_string(),
+            ));
+        }
+
+        Ok(HistoricRange {
+            client: self,
+            query: query.clone(),
+            checkpoint: SweepCheckpoint {
+                from,
+                to,
+                step,
+                next: from,
+                credits_used: 0,
+                query: Self::build_query_params(query)?,
+            },
+            credit_budget: None,
+            checkpoint_path: None,
+            stopped: false,
+        })
+    }
+}
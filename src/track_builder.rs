@@ -0,0 +1,150 @@
+/// Reconstruction of per-flight tracks from snapshot sequences
+use crate::client::{Flight, FullLiveFlightData, FullLiveFlightResponse, Track};
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+
+/// Change of a callsign or squawk between two consecutive positions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValueChange {
+    /// Time of the first position with the new value
+    pub timestamp: DateTime<Utc>,
+    pub previous: String,
+    pub current: String,
+}
+
+/// Track rebuilt from snapshots with the callsign and squawk history
+#[derive(Debug, Clone)]
+pub struct ReconstructedFlight {
+    pub flight: Flight,
+    pub callsign_changes: Vec<ValueChange>,
+    pub squawk_changes: Vec<ValueChange>,
+}
+
+/// Collects positions from snapshots and groups them into tracks by `fr24_id`
+#[derive(Debug, Default)]
+pub struct TrackBuilder {
+    positions: BTreeMap<String, BTreeMap<DateTime<Utc>, Track>>,
+    skipped: usize,
+}
+
+impl TrackBuilder {
+    /// Creates a builder with no positions
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds every aircraft of a snapshot
+    /// # Arguments
+    ///   * `snapshot` - Response from the `flight-positions` endpoints
+    pub fn add_snapshot(&mut self, snapshot: &FullLiveFlightResponse) {
+        for flight in &snapshot.data {
+            self.add_position(flight);
+        }
+    }
+
+    /// Adds a single position.
+    ///
+    /// Positions without `fr24_id` or with an unreadable timestamp are
+    /// skipped. A timestamp already seen for the flight keeps its first
+    /// position, historic snapshots repeat the last report of an aircraft
+    /// until a new one arrives.
+    /// # Arguments
+    ///   * `position` - Aircraft state from a snapshot
+    pub fn add_position(&mut self, position: &FullLiveFlightData) {
+        let timestamp = match position.timestamp.parse::<DateTime<Utc>>() {
+            Ok(timestamp) if !position.fr24_id.is_empty() => timestamp,
+            _ => {
+                self.skipped += 1;
+                return;
+            }
+        };
+
+        self.positions
+            .entry(position.fr24_id.clone())
+            .or_default()
+            .entry(timestamp)
+            .or_insert_with(|| Track {
+                timestamp: position.timestamp.clone(),
+                lat: position.lat,
+                lon: position.lon,
+                alt: position.alt,
+                gspeed: position.gspeed,
+                vspeed: position.vspeed,
+                track: position.track,
+                squawk: position.squawk.trim().to_string(),
+                callsign: position.callsign.trim().to_string(),
+                source: position.source.clone(),
+            });
+    }
+
+    /// Number of flights seen so far
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Whether no position has been added
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Positions skipped for a missing `fr24_id` or unreadable timestamp
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+
+    /// Builds the tracks.
+    ///
+    /// Points are in time order. A blank callsign or squawk takes the last
+    /// known value, or the first known one for leading points, so gaps in
+    /// the feed are not reported as changes.
+    /// # Returns
+    ///   One `ReconstructedFlight` per `fr24_id`, ordered by `fr24_id`
+    pub fn build(self) -> Vec<ReconstructedFlight> {
+        self.positions
+            .into_iter()
+            .map(|(id, positions)| {
+                let (times, mut tracks): (Vec<DateTime<Utc>>, Vec<Track>) =
+                    positions.into_iter().unzip();
+
+                let callsign_changes = reconcile(&mut tracks, &times, |t| &mut t.callsign);
+                let squawk_changes = reconcile(&mut tracks, &times, |t| &mut t.squawk);
+
+                ReconstructedFlight {
+                    flight: Flight { id, tracks },
+                    callsign_changes,
+                    squawk_changes,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Fills blank values from their neighbours and lists the remaining changes
+fn reconcile<F>(tracks: &mut [Track], times: &[DateTime<Utc>], field: F) -> Vec<ValueChange>
+where
+    F: Fn(&mut Track) -> &mut String,
+{
+    let Some(first) = tracks
+        .iter_mut()
+        .map(|t| field(t).clone())
+        .find(|v| !v.is_empty())
+    else {
+        return Vec::new();
+    };
+
+    let mut changes = Vec::new();
+    let mut last = first;
+    for (track, timestamp) in tracks.iter_mut().zip(times) {
+        let value = field(track);
+        if value.is_empty() {
+            value.clone_from(&last);
+        } else if *value != last {
+            changes.push(ValueChange {
+                timestamp: *timestamp,
+                previous: std::mem::replace(&mut last, value.clone()),
+                current: value.clone(),
+            });
+        }
+    }
+    changes
+}
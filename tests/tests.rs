@@ -2,8 +2,24 @@
 mod tests {
 
     use dotenv::dotenv;
+    use flightradar24_api::board::*;
+    use flightradar24_api::cache::*;
+    use flightradar24_api::cassette::*;
     use flightradar24_api::client::*;
+    use flightradar24_api::enrich::*;
+    use flightradar24_api::eta::*;
+    use flightradar24_api::events::*;
+    use flightradar24_api::export::*;
+    use flightradar24_api::filter::*;
     use flightradar24_api::flight_tracks_helper::*;
+    use flightradar24_api::icao24::*;
+    use flightradar24_api::rate_limit::*;
+    use flightradar24_api::runways::*;
+    use flightradar24_api::separation::*;
+    use flightradar24_api::smoothing::*;
+    use flightradar24_api::spatial::*;
+    use flightradar24_api::sweep::*;
+    use flightradar24_api::track_builder::*;
 
     const BOUNDS_IN: Bounds = Bounds {
         north: 42.473,
@@ -22,6 +38,19 @@ mod tests {
         client
     }
 
+    fn make_track(timestamp: &str, lat: f64, lon: f64, alt: u32, track: u32) -> Track {
+        Track {
+            timestamp: timestamp.to_string(),
+            lat,
+            lon,
+            alt,
+            gspeed: 400,
+            track,
+            source: "ADSB".to_string(),
+            ..Track::default()
+        }
+    }
+
     #[test]
     fn check_flight_tracks_by_id() {
         let client = setup_client();
@@ -149,12 +178,12 @@ mod tests {
                 "ART".to_string(),
                 "aaa".to_string(),
             ]),
-            airports: Some(vec!["LHR".to_string(), "both:ESSA".to_string()]),
-            routes: Some(vec!["SE-US".to_string(), "ESSA-JFK".to_string()]),
+            airports: Some(vec!["LHR".parse().unwrap(), "both:ESSA".parse().unwrap()]),
+            routes: Some(vec!["SE-US".parse().unwrap(), "ESSA-JFK".parse().unwrap()]),
             aircraft: Some(vec![
-                "B38M".to_string(),
-                "A32*".to_string(),
-                "*33".to_string(),
+                "B38M".parse().unwrap(),
+                "A32*".parse().unwrap(),
+                "*33".parse().unwrap(),
             ]),
             altitude_ranges: Some(vec![ApiRange {
                 max: 3333,
@@ -205,12 +234,12 @@ mod tests {
                 "ART".to_string(),
                 "aaa".to_string(),
             ]),
-            airports: Some(vec!["LHR".to_string(), "both:ESSA".to_string()]),
-            routes: Some(vec!["SE-US".to_string(), "ESSA-JFK".to_string()]),
+            airports: Some(vec!["LHR".parse().unwrap(), "both:ESSA".parse().unwrap()]),
+            routes: Some(vec!["SE-US".parse().unwrap(), "ESSA-JFK".parse().unwrap()]),
             aircraft: Some(vec![
-                "B38M".to_string(),
-                "A32*".to_string(),
-                "*33".to_string(),
+                "B38M".parse().unwrap(),
+                "A32*".parse().unwrap(),
+                "*33".parse().unwrap(),
             ]),
             altitude_ranges: Some(vec![ApiRange {
                 max: 3333,
@@ -262,12 +291,12 @@ mod tests {
                 "ART".to_string(),
                 "aaa".to_string(),
             ]),
-            airports: Some(vec!["LHR".to_string(), "both:ESSA".to_string()]),
-            routes: Some(vec!["SE-US".to_string(), "ESSA-JFK".to_string()]),
+            airports: Some(vec!["LHR".parse().unwrap(), "both:ESSA".parse().unwrap()]),
+            routes: Some(vec!["SE-US".parse().unwrap(), "ESSA-JFK".parse().unwrap()]),
             aircraft: Some(vec![
-                "B38M".to_string(),
-                "A32*".to_string(),
-                "*33".to_string(),
+                "B38M".parse().unwrap(),
+                "A32*".parse().unwrap(),
+                "*33".parse().unwrap(),
             ]),
             altitude_ranges: Some(vec![ApiRange {
                 max: 3333,
@@ -319,12 +348,12 @@ mod tests {
                 "ART".to_string(),
                 "aaa".to_string(),
             ]),
-            airports: Some(vec!["LHR".to_string(), "both:ESSA".to_string()]),
-            routes: Some(vec!["SE-US".to_string(), "ESSA-JFK".to_string()]),
+            airports: Some(vec!["LHR".parse().unwrap(), "both:ESSA".parse().unwrap()]),
+            routes: Some(vec!["SE-US".parse().unwrap(), "ESSA-JFK".parse().unwrap()]),
             aircraft: Some(vec![
-                "B38M".to_string(),
-                "A32*".to_string(),
-                "*33".to_string(),
+                "B38M".parse().unwrap(),
+                "A32*".parse().unwrap(),
+                "*33".parse().unwrap(),
             ]),
             altitude_ranges: Some(vec![ApiRange {
                 max: 3333,
@@ -352,4 +381,1269 @@ mod tests {
 
         assert_eq!(2, historic_flight_light.data.len());
     }
+
+    #[test]
+    fn check_resample_interpolation() {
+        let flight = Flight {
+            id: "390163bf".to_string(),
+            tracks: vec![
+                make_track("2025-02-13T00:00:00Z", 50.0, 10.0, 10000, 350),
+                make_track("2025-02-13T00:01:00Z", 50.0, 11.0, 12000, 10),
+                make_track("2025-02-13T00:11:00Z", 50.0, 12.0, 12000, 10),
+            ],
+        };
+
+        let resampled = flight.resample(chrono::Duration::seconds(30)).unwrap();
+
+        assert_eq!(23, resampled.points.len());
+        let middle = resampled.points[1].state.as_ref().unwrap();
+        assert_eq!(11000.0, middle.alt);
+        assert!(middle.track.abs() < 1e-9);
+        assert!(middle.lat > 50.0 && (middle.lon - 10.5).abs() < 1e-9);
+        assert_eq!(19, resampled.gap_count());
+    }
+
+    #[test]
+    fn check_track_export() {
+        let flights = vec![Flight {
+            id: "390163bf".to_string(),
+            tracks: vec![
+                make_track("2025-02-13T00:00:00Z", 50.0, 10.0, 10000, 90),
+                make_track("2025-02-13T00:01:00Z", 50.0, 11.0, 12000, 90),
+            ],
+        }];
+
+        let geojson = flights_to_geojson(&flights);
+        assert_eq!(3, geojson["features"].as_array().unwrap().len());
+        assert_eq!("LineString", geojson["features"][0]["geometry"]["type"]);
+
+        let kml = flights_to_kml(&flights);
+        assert_eq!(2, kml.matches("<gx:coord>").count());
+        assert_eq!(2, kml.matches("<when>").count());
+
+        let gpx = flights_to_gpx(&flights);
+        assert_eq!(2, gpx.matches("<trkpt ").count());
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn check_parquet_partitions() {
+        use flightradar24_api::columnar::*;
+
+        let flights = vec![Flight {
+            id: "390163bf".to_string(),
+            tracks: vec![
+                make_track("2025-02-13T00:59:00Z", 50.0, 10.0, 10000, 90),
+                make_track("2025-02-13T01:01:00Z", 50.0, 11.0, 12000, 90),
+            ],
+        }];
+
+        let batch = flights_to_record_batch(&flights).unwrap();
+        assert_eq!(2, batch.num_rows());
+
+        let root = std::env::temp_dir().join(format!("fr24-parquet-{}", std::process::id()));
+        let written = ParquetPartitionWriter::new(&root).write(&batch).unwrap();
+        assert_eq!(2, written.len());
+        assert!(written[0].to_string_lossy().contains("date=2025-02-13"));
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn check_store_queries() {
+        use chrono::{TimeZone, Utc};
+        use flightradar24_api::store::*;
+
+        let store = Store::open_in_memory().unwrap();
+        let snapshot = FullLiveFlightResponse {
+            data: vec![FullLiveFlightData {
+                fr24_id: "390163bf".to_string(),
+                lat: 40.0,
+                lon: -5.0,
+                timestamp: "2025-02-13T00:00:00Z".to_string(),
+                ..FullLiveFlightData::default()
+            }],
+        };
+        assert_eq!(1, store.insert_full_live(&snapshot).unwrap());
+
+        let from = Utc.with_ymd_and_hms(2025, 2, 12, 0, 0, 0).unwrap();
+        let to = Utc.with_ymd_and_hms(2025, 2, 14, 0, 0, 0).unwrap();
+        assert_eq!(1, store.positions("390163bf", from, to).unwrap().len());
+        assert_eq!(
+            vec!["390163bf".to_string()],
+            store.flights_in_bounds(&BOUNDS_IN, from, to).unwrap()
+        );
+        assert!(store.airport("MCO").unwrap().is_none());
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn check_store_archived_tracks_in_bounds() {
+        use chrono::{TimeZone, Utc};
+        use flightradar24_api::store::*;
+
+        let store = Store::open_in_memory().unwrap();
+        let flight = Flight {
+            id: "3a4b5c6d".to_string(),
+            tracks: vec![make_track("2025-02-13T00:00:00Z", 40.0, -5.0, 10000, 90)],
+        };
+        assert_eq!(1, store.insert_flights(&[flight]).unwrap());
+
+        let from = Utc.with_ymd_and_hms(2025, 2, 12, 0, 0, 0).unwrap();
+        let to = Utc.with_ymd_and_hms(2025, 2, 14, 0, 0, 0).unwrap();
+        assert_eq!(1, store.positions("3a4b5c6d", from, to).unwrap().len());
+
+        // A snapshot at the same time replaces the track point, not duplicates it
+        let snapshot = FullLiveFlightResponse {
+            data: vec![FullLiveFlightData {
+                fr24_id: "3a4b5c6d".to_string(),
+                lat: 40.0,
+                lon: -5.0,
+                timestamp: "2025-02-13T00:00:00Z".to_string(),
+                ..FullLiveFlightData::default()
+            }],
+        };
+        store.insert_full_live(&snapshot).unwrap();
+        assert_eq!(1, store.positions("3a4b5c6d", from, to).unwrap().len());
+        assert_eq!(
+            vec!["3a4b5c6d".to_string()],
+            store.flights_in_bounds(&BOUNDS_IN, from, to).unwrap()
+        );
+    }
+
+    #[test]
+    fn check_memory_cache_eviction() {
+        let cache = MemoryCache::new(2);
+        cache.put("a", "1", None);
+        cache.put("b", "2", None);
+        assert_eq!(Some("1".to_string()), cache.get("a"));
+        cache.put("c", "3", None);
+
+        assert_eq!(None, cache.get("b"));
+        assert_eq!(2, cache.len());
+
+        cache.put("d", "4", Some(std::time::Duration::ZERO));
+        assert_eq!(None, cache.get("d"));
+    }
+
+    #[test]
+    fn check_cached_airline_lookup() {
+        let cache = MemoryCache::new(16);
+        cache.put(
+            "https://fr24api.flightradar24.com/api/static/airlines/AAA/light",
+            r#"{"name":"Test Air","iata":null,"icao":"AAA"}"#,
+            None,
+        );
+
+        let mut client = FlightRadarClient::new("unused".to_string());
+        client.set_cache(Box::new(cache), CachePolicy::default());
+
+        let airline = client.get_airline_by_icao("AAA").unwrap();
+        assert_eq!("Test Air", airline.name);
+        let (_, _, source) = client
+            .query_endpoint_with_source(
+                "https://fr24api.flightradar24.com/api/static/airlines/AAA/light".to_string(),
+            )
+            .unwrap();
+        assert_eq!(ResponseSource::Cache, source);
+
+        let stats = client.cache_stats().unwrap();
+        assert_eq!(2, stats.hits());
+        assert_eq!(2, stats.endpoints[&EndpointKind::Static].hits);
+    }
+
+    #[test]
+    fn check_cassette_replay() {
+        let path = std::env::temp_dir().join(format!("fr24-cassette-{}.json", std::process::id()));
+        let cassette = Cassette {
+            interactions: vec![Interaction {
+                url: "https://fr24api.flightradar24.com/api/flight-tracks?flight_id=390163bf"
+                    .to_string(),
+                status: 200,
+                body: r#"[{"fr24_id":"390163bf","tracks":[{"timestamp":"2025-02-13T00:00:00Z",
+                    "lat":50.0,"lon":10.0,"alt":10000,"gspeed":400,"vspeed":0,"track":90,
+                    "squawk":"1000","callsign":"TEST1","source":"ADSB"}]}]"#
+                    .to_string(),
+            }],
+        };
+        cassette.save(&path).unwrap();
+
+        let mut client = FlightRadarClient::new("secret".to_string());
+        client.replay_cassette(&path).unwrap();
+
+        let flights = client.get_flight_tracks_by_id("390163bf").unwrap();
+        assert_eq!(1, get_callsign_from_flight(&flights).len());
+        assert!(client.get_flight_tracks_by_id("390163be").is_err());
+        assert_eq!("<API_KEY>/x", scrub_api_key("secret/x", "secret"));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn check_cassette_records_cache_hits() {
+        let path =
+            std::env::temp_dir().join(format!("fr24-cassette-cache-{}.json", std::process::id()));
+        let url = "https://fr24api.flightradar24.com/api/static/airlines/AAA/light";
+        let cache = MemoryCache::new(16);
+        cache.put(url, r#"{"name":"Test Air","iata":null,"icao":"AAA"}"#, None);
+
+        let mut client = FlightRadarClient::new("secret".to_string());
+        client.set_cache(Box::new(cache), CachePolicy::default());
+        client.record_cassette(&path);
+        assert_eq!("Test Air", client.get_airline_by_icao("AAA").unwrap().name);
+
+        let mut replay = FlightRadarClient::new("secret".to_string());
+        replay.replay_cassette(&path).unwrap();
+        assert_eq!("Test Air", replay.get_airline_by_icao("AAA").unwrap().name);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn check_rate_limiter_burst() {
+        let limiter = RateLimiter::new(2, std::time::Duration::from_secs(60));
+        assert!(limiter.try_acquire().is_ok());
+        assert!(limiter.try_acquire().is_ok());
+        let wait = limiter.try_acquire().unwrap_err();
+        assert!(wait > std::time::Duration::from_secs(25));
+        assert!(wait <= std::time::Duration::from_secs(30));
+    }
+
+    fn make_position(id: &str, lat: f64, lon: f64, alt: u32, track: u32) -> FullLiveFlightData {
+        FullLiveFlightData {
+            fr24_id: id.to_string(),
+            lat,
+            lon,
+            alt,
+            track,
+            gspeed: 450,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn check_conflict_detection() {
+        // Head-on at 900 kt closure, 20 nm apart along the equator
+        let flights = vec![
+            make_position("a", 0.0, 0.0, 35000, 90),
+            make_position("b", 0.0, 20.0 / 60.0, 35000, 270),
+            make_position("c", 0.0, 20.0 / 60.0, 40000, 270),
+            make_position("d", 10.0, 10.0, 35000, 0),
+            make_position("e", 0.0, 0.1, 0, 270),
+        ];
+
+        let cpa =
+            closest_point_of_approach(&flights[0], &flights[1], chrono::Duration::seconds(300));
+        assert!((cpa.time_to_cpa.num_seconds() - 80).abs() <= 1);
+        assert!(cpa.horizontal_nm < 0.01);
+        assert_eq!(0.0, cpa.vertical_ft);
+
+        let conflicts = detect_conflicts(&flights, &SeparationMinima::default());
+        assert_eq!(1, conflicts.len());
+        assert_eq!(
+            ("a", "b"),
+            (conflicts[0].first.as_str(), conflicts[0].second.as_str())
+        );
+        assert!((conflicts[0].time_to_loss.num_seconds() - 60).abs() <= 1);
+
+        let short = SeparationMinima {
+            lookahead: chrono::Duration::seconds(30),
+            ..Default::default()
+        };
+        assert!(detect_conflicts(&flights, &short).is_empty());
+    }
+
+    #[test]
+    fn check_snapshot_index_queries() {
+        let flights = vec![
+            make_position("near", 51.5, -0.1, 3000, 0),
+            make_position("route", 52.0, 1.0, 20000, 90),
+            make_position("far", 40.0, -3.7, 35000, 180),
+            make_position("corner", 51.95, 0.6, 5000, 270),
+        ];
+        let index = SnapshotIndex::new(&flights);
+
+        let nearest = index.nearest(51.48, -0.1, 2);
+        assert_eq!(
+            vec!["near", "corner"],
+            nearest
+                .iter()
+                .map(|(f, _)| f.fr24_id.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert!((nearest[0].1 - 1.2).abs() < 0.05);
+
+        let within = index.within_radius(51.48, -0.1, 30.0);
+        assert_eq!(1, within.len());
+
+        let route = index.along_route(&[(51.5, -1.0), (52.0, 0.0), (52.0, 2.0)], 5.0);
+        assert_eq!(
+            vec!["corner", "route"],
+            route
+                .iter()
+                .map(|(f, _)| f.fr24_id.as_str())
+                .collect::<Vec<_>>()
+        );
+
+        let bounds = Bounds::around(51.48, -0.1, 30.0);
+        assert!((bounds.north - 51.98).abs() < 0.01 && (bounds.south - 50.98).abs() < 0.01);
+        assert!(bounds.west < -0.9 && bounds.east > 0.7 && bounds.east < 0.75);
+        let polar = Bounds::around(89.9, 0.0, 30.0);
+        assert_eq!((-180.0, 180.0), (polar.west, polar.east));
+    }
+
+    #[test]
+    fn check_airport_board() {
+        let airport = Airport {
+            iata: "ARN".to_string(),
+            icao: "ESSA".to_string(),
+            lat: 59.65,
+            lon: 17.93,
+            timezone: Timezone {
+                name: "Europe/Stockholm".to_string(),
+                offset: 3600,
+            },
+            ..Default::default()
+        };
+        let route = |id: &str, lat: f64, lon: f64, alt: u32, orig: &str, dest: &str, eta: &str| {
+            FullLiveFlightData {
+                orig_iata: orig.to_string(),
+                dest_iata: dest.to_string(),
+                eta: eta.to_string(),
+                timestamp: "2025-02-13T10:00:00Z".to_string(),
+                ..make_position(id, lat, lon, alt, 0)
+            }
+        };
+        let flights = vec![
+            route(
+                "late",
+                55.0,
+                12.0,
+                30000,
+                "CPH",
+                "ARN",
+                "2025-02-13T11:30:00Z",
+            ),
+            route(
+                "soon",
+                59.0,
+                17.0,
+                8000,
+                "LHR",
+                "ARN",
+                "2025-02-13T10:20:00Z",
+            ),
+            route("landed", 59.651, 17.931, 0, "OSL", "ARN", ""),
+            route(
+                "out",
+                60.0,
+                18.5,
+                12000,
+                "ARN",
+                "HEL",
+                "2025-02-13T11:00:00Z",
+            ),
+            route("other", 50.0, 8.0, 35000, "FRA", "MAD", ""),
+        ];
+
+        let board = AirportBoard::from_flights(airport, &flights);
+        let arrivals: Vec<&str> = board.arrivals.iter().map(|e| e.fr24_id.as_str()).collect();
+        assert_eq!(vec!["landed", "soon", "late"], arrivals);
+        assert_eq!(FlightStatus::Landed, board.arrivals[0].status);
+        assert_eq!(FlightStatus::EnRoute, board.arrivals[1].status);
+        assert_eq!(
+            "2025-02-13T11:20:00+01:00",
+            board.arrivals[1].eta.unwrap().to_rfc3339()
+        );
+        assert_eq!(1, board.departures.len());
+        assert_eq!(FlightStatus::Departed, board.departures[0].status);
+        assert_eq!("HEL", board.departures[0].other_airport);
+    }
+
+    #[test]
+    fn check_airport_board_ground_away() {
+        let airport = Airport {
+            iata: "ARN".to_string(),
+            lat: 59.65,
+            lon: 17.93,
+            ..Default::default()
+        };
+        let grounded = |id: &str, lat: f64, lon: f64, orig: &str, dest: &str| FullLiveFlightData {
+            orig_iata: orig.to_string(),
+            dest_iata: dest.to_string(),
+            ..make_position(id, lat, lon, 0, 0)
+        };
+        let flights = vec![
+            grounded("gate", 59.652, 17.932, "ARN", "HEL"),
+            grounded("arrived", 60.32, 24.96, "ARN", "HEL"),
+            grounded("boarding", 55.62, 12.65, "CPH", "ARN"),
+        ];
+
+        let board = AirportBoard::from_flights(airport, &flights);
+        let departures: Vec<(&str, FlightStatus)> = board
+            .departures
+            .iter()
+            .map(|e| (e.fr24_id.as_str(), e.status))
+            .collect();
+        assert_eq!(
+            vec![
+                ("gate", FlightStatus::Taxiing),
+                ("arrived", FlightStatus::Departed)
+            ],
+            departures
+        );
+        assert_eq!(1, board.arrivals.len());
+        assert_eq!(FlightStatus::EnRoute, board.arrivals[0].status);
+    }
+
+    #[test]
+    fn check_airport_filters() {
+        let filter: AirportFilter = "inbound:essa".parse().unwrap();
+        assert_eq!(AirportDirection::Inbound, filter.direction);
+        assert_eq!(AirportCode::Icao("ESSA".to_string()), filter.code);
+        assert_eq!(
+            "both:LHR",
+            "LHR".parse::<AirportFilter>().unwrap().to_string()
+        );
+        assert_eq!(
+            "outbound:SE",
+            AirportFilter::outbound("se").unwrap().to_string()
+        );
+        assert_eq!(
+            AirportCode::Icao("EG74".to_string()),
+            "EG74".parse::<AirportCode>().unwrap()
+        );
+
+        for bad in ["", "E", "ESSAX", "LH1", "1ESS", "sideways:ESSA", "both:"] {
+            assert!(bad.parse::<AirportFilter>().is_err(), "{}", bad);
+        }
+
+        assert_eq!(
+            "SE-JFK",
+            "se-jfk".parse::<RouteFilter>().unwrap().to_string()
+        );
+        assert!("ESSA".parse::<RouteFilter>().is_err());
+        assert!("ESSA-J".parse::<RouteFilter>().is_err());
+
+        let query: FullLiveFlightQuery =
+            serde_json::from_str(r#"{"airports":["inbound:ARN"],"routes":["SE-US"]}"#).unwrap();
+        assert_eq!(1, query.routes.unwrap().len());
+        assert!(serde_json::from_str::<FullLiveFlightQuery>(r#"{"airports":["A:B:C"]}"#).is_err());
+    }
+
+    #[test]
+    fn check_aircraft_type_filters() {
+        let filters: Vec<AircraftTypeFilter> = ["b38m", "A32*", "*33", "A3*9", "family:787"]
+            .iter()
+            .map(|f| f.parse().unwrap())
+            .collect();
+        assert_eq!(AircraftTypeFilter::Exact("B38M".to_string()), filters[0]);
+        assert!(filters[1].matches("a321"));
+        assert!(!filters[1].matches("A332"));
+        assert!(filters[2].matches("A333"));
+        assert!(filters[3].matches("A359") && filters[3].matches("A319"));
+        assert!(!filters[3].matches("A388"));
+        assert!(filters[4].matches("B78X") && !filters[4].matches("B772"));
+        assert_eq!(3, filters[4].api_values().len());
+
+        for bad in ["", "*", "A**", "*3*", "B7-8", "A3211", "family:DC3"] {
+            assert!(bad.parse::<AircraftTypeFilter>().is_err(), "{}", bad);
+        }
+
+        let snapshot = FullLiveFlightResponse {
+            data: ["B38M", "A20N", "B789", "E190"]
+                .iter()
+                .map(|t| FullLiveFlightData {
+                    type_field: t.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+        };
+        let family: AircraftTypeFilter = "family:A320".parse().unwrap();
+        assert_eq!(
+            2,
+            snapshot
+                .filter_aircraft(&[filters[0].clone(), family])
+                .len()
+        );
+        assert_eq!(4, snapshot.filter_aircraft(&[]).len());
+    }
+
+    #[cfg(feature = "aircraft-db")]
+    #[test]
+    fn check_aircraft_type_info() {
+        use flightradar24_api::aircraft_db::*;
+
+        let flight = FullLiveFlightData {
+            type_field: "B38M".to_string(),
+            ..Default::default()
+        };
+        let info = flight.aircraft_type_info().unwrap();
+        assert_eq!("Boeing 737 MAX 8", info.full_name());
+        assert_eq!(2, info.engine_count);
+        assert_eq!(EngineType::Jet, info.engine_type);
+        assert_eq!(WakeCategory::Medium, info.wake_category);
+
+        assert_eq!(
+            WakeCategory::Super,
+            aircraft_type("a388").unwrap().wake_category
+        );
+        assert!(aircraft_type("ZZZZ").is_none());
+        assert_eq!(
+            include_str!("../data/aircraft_types.csv").lines().count() - 1,
+            aircraft_types().count()
+        );
+    }
+
+    #[test]
+    fn check_icao24_decoding() {
+        let first: Icao24 = "a00001".parse().unwrap();
+        assert_eq!("A00001", first.to_string());
+        assert_eq!(Some("United States"), first.country());
+        assert_eq!(Some("N1".to_string()), first.registration());
+        assert_eq!(
+            Some("N1A".to_string()),
+            Icao24::new(0xA00002).unwrap().registration()
+        );
+        assert_eq!(
+            Some("N1AA".to_string()),
+            Icao24::new(0xA00003).unwrap().registration()
+        );
+        assert_eq!(
+            0xADF7C7,
+            Icao24::from_registration("N99999").unwrap().address()
+        );
+        for address in 0xA00001..=0xADF7C7 {
+            let registration = Icao24::new(address).unwrap().registration().unwrap();
+            assert_eq!(
+                address,
+                Icao24::from_registration(&registration).unwrap().address(),
+                "{}",
+                registration
+            );
+        }
+        assert!(Icao24::from_registration("N0123").is_none());
+        assert!(Icao24::from_registration("N1I").is_none());
+
+        assert_eq!(
+            0xC00001,
+            Icao24::from_registration("C-FAAA").unwrap().address()
+        );
+        assert_eq!(
+            0xC044A9,
+            Icao24::from_registration("c-gaaa").unwrap().address()
+        );
+        assert_eq!(
+            Some("C-GZZZ".to_string()),
+            Icao24::new(0xC08950).unwrap().registration()
+        );
+
+        let sweden: Icao24 = "4AC9E3".parse().unwrap();
+        assert_eq!(Some("Sweden"), sweden.country());
+        assert_eq!(
+            RegistrationCheck::Consistent,
+            sweden.check_registration("SE-RUA")
+        );
+        assert_eq!(
+            RegistrationCheck::Mismatch,
+            sweden.check_registration("D-AIBL")
+        );
+        assert_eq!(RegistrationCheck::Mismatch, first.check_registration("N2"));
+
+        assert_eq!(
+            AddressKind::NonIcao,
+            "~1A2B3C".parse::<Icao24>().unwrap().kind()
+        );
+        assert_eq!(AddressKind::Anonymous, Icao24::new(0).unwrap().kind());
+        assert_eq!(
+            AddressKind::Unallocated,
+            Icao24::new(0x200000).unwrap().kind()
+        );
+        assert_eq!(
+            AddressKind::IcaoReserved,
+            Icao24::new(0xF00010).unwrap().kind()
+        );
+        assert!("".parse::<Icao24>().is_err() && "1234567".parse::<Icao24>().is_err());
+    }
+
+    #[test]
+    fn check_batch_airline_lookup() {
+        let cache = MemoryCache::new(16);
+        for icao in ["AAA", "BBB"] {
+            cache.put(
+                &format!("http://127.0.0.1:9/static/airlines/{}/light", icao),
+                &format!(
+                    r#"{{"name":"Airline {}","iata":null,"icao":"{}"}}"#,
+                    icao, icao
+                ),
+                None,
+            );
+        }
+
+        let mut client = FlightRadarClient::new("unused".to_string());
+        client.update_base_url("http://127.0.0.1:9/".to_string());
+        client.set_cache(Box::new(cache), CachePolicy::default());
+
+        let airlines = client.get_airlines(&["aaa", "BBB", "AAA ", "ZZZ", ""]);
+        assert_eq!(3, airlines.len());
+        assert_eq!("Airline AAA", airlines["AAA"].as_ref().unwrap().name);
+        assert_eq!("Airline BBB", airlines["BBB"].as_ref().unwrap().name);
+        assert!(airlines["ZZZ"].is_err());
+        assert_eq!(2, client.cache_stats().unwrap().hits());
+    }
+
+    #[test]
+    fn check_flight_enrichment() {
+        let cache = MemoryCache::new(16);
+        let base = "http://127.0.0.1:9/";
+        cache.put(
+            &format!("{}static/airports/ESSA/full", base),
+            r#"{"name":"Stockholm Arlanda","iata":"ARN","icao":"ESSA","lon":17.918,"lat":59.652,
+                "elevation":137,"country":{"code":"SE","name":"Sweden"},"city":"Stockholm",
+                "state":null,"timezone":{"name":"Europe/Stockholm","offset":3600}}"#,
+            None,
+        );
+        cache.put(
+            &format!("{}static/airlines/SAS/light", base),
+            r#"{"name":"SAS","iata":"SK","icao":"SAS"}"#,
+            None,
+        );
+
+        let mut client = FlightRadarClient::new("unused".to_string());
+        client.update_base_url(base.to_string());
+        client.set_cache(Box::new(cache), CachePolicy::default());
+
+        let mut enricher = Enricher::new(&client);
+        enricher.insert_airport(Airport {
+            name: "Copenhagen".to_string(),
+            iata: "CPH".to_string(),
+            icao: "EKCH".to_string(),
+            lat: 55.618,
+            lon: 12.656,
+            ..Default::default()
+        });
+
+        let snapshot = FullLiveFlightResponse {
+            data: vec![
+                FullLiveFlightData {
+                    orig_iata: "CPH".to_string(),
+                    dest_icao: "ESSA".to_string(),
+                    operating_as: "SAS".to_string(),
+                    painted_as: "XXX".to_string(),
+                    ..make_position("a", 57.5, 15.0, 30000, 45)
+                },
+                FullLiveFlightData {
+                    orig_icao: "ZZZZ".to_string(),
+                    ..make_position("b", 50.0, 10.0, 30000, 45)
+                },
+            ],
+        };
+
+        let enriched = enricher.enrich(&snapshot);
+        assert_eq!(2, enriched.len());
+        assert_eq!("Copenhagen", enriched[0].origin.as_ref().unwrap().name);
+        assert_eq!("ARN", enriched[0].destination.as_ref().unwrap().iata);
+        assert_eq!("SAS", enriched[0].operating_airline.as_ref().unwrap().name);
+        assert!(enriched[0].painted_airline.is_none());
+        assert!((enriched[0].route_distance_nm.unwrap() - 295.0).abs() < 2.0);
+        let progress = enriched[0].progress().unwrap();
+        assert!(progress > 0.3 && progress < 0.7);
+        assert!(enriched[1].origin.is_none() && enriched[1].route_distance_nm.is_none());
+        // Unreachable codes are retried
+        assert!(enricher.failed_lookups().is_empty());
+        let misses = client.cache_stats().unwrap().misses();
+        enricher.enrich(&snapshot);
+        assert_eq!(misses + 2, client.cache_stats().unwrap().misses());
+    }
+
+    #[test]
+    fn check_enrichment_lookup_statuses() {
+        let path =
+            std::env::temp_dir().join(format!("fr24-cassette-enrich-{}.json", std::process::id()));
+        let airport = |code: &str| {
+            format!(
+                "https://fr24api.flightradar24.com/api/static/airports/{}/full",
+                code
+            )
+        };
+        let interaction = |url: String, status: u16, body: &str| Interaction {
+            url,
+            status,
+            body: body.to_string(),
+        };
+        Cassette {
+            interactions: vec![
+                interaction(airport("ESSA"), 401, r#"{"message":"Unauthenticated."}"#),
+                interaction(
+                    airport("ESSA"),
+                    200,
+                    r#"{"name":"Stockholm Arlanda","iata":"ARN","icao":"ESSA","lon":17.918,
+                        "lat":59.652,"elevation":137,"country":{"code":"SE","name":"Sweden"},
+                        "city":"Stockholm","state":null,
+                        "timezone":{"name":"Europe/Stockholm","offset":3600}}"#,
+                ),
+                interaction(airport("ZZZZ"), 404, r#"{"message":"Not found"}"#),
+            ],
+        }
+        .save(&path)
+        .unwrap();
+
+        let mut client = FlightRadarClient::new("secret".to_string());
+        client.replay_cassette(&path).unwrap();
+        let snapshot = FullLiveFlightResponse {
+            data: vec![FullLiveFlightData {
+                orig_icao: "ZZZZ".to_string(),
+                dest_icao: "ESSA".to_string(),
+                ..make_position("a", 57.5, 15.0, 30000, 45)
+            }],
+        };
+
+        // A rejected key is retried, an unknown code is remembered
+        let mut enricher = Enricher::new(&client);
+        assert!(enricher.enrich(&snapshot)[0].destination.is_none());
+        assert_eq!(
+            vec!["ZZZZ"],
+            enricher.failed_lookups().into_iter().collect::<Vec<_>>()
+        );
+        let enriched = enricher.enrich(&snapshot);
+        assert_eq!("ARN", enriched[0].destination.as_ref().unwrap().iata);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn check_eta_estimate() {
+        let destination = Airport {
+            icao: "ESSA".to_string(),
+            lat: 0.0,
+            lon: 0.0,
+            ..Default::default()
+        };
+        // 200 nm south of the airport at FL360 and 450 kt
+        let flight = FullLiveFlightData {
+            timestamp: "2025-02-13T10:00:00Z".to_string(),
+            eta: "2025-02-13T10:38:00Z".to_string(),
+            ..make_position("a", -200.0 / 60.0, 0.0, 36000, 0)
+        };
+
+        let estimate = EtaModel::default()
+            .estimate(&flight, &destination, None)
+            .unwrap();
+        let now: chrono::DateTime<chrono::Utc> = flight.timestamp.parse().unwrap();
+        let minutes = |t: chrono::DateTime<chrono::Utc>| (t - now).num_seconds() as f64 / 60.0;
+        assert!((minutes(estimate.eta) - 39.5).abs() < 0.2);
+        assert!((minutes(estimate.earliest) - 35.5).abs() < 0.2);
+        assert!((minutes(estimate.latest) - 45.5).abs() < 0.2);
+        assert!(estimate.fr24_within_band().unwrap());
+        let difference = estimate.fr24_difference().unwrap().num_seconds();
+        assert!((80..100).contains(&difference));
+
+        // Closing 40 nm over the last five minutes is 480 kt
+        let history = Flight {
+            id: "a".to_string(),
+            tracks: vec![
+                make_track("2025-02-13T09:55:00Z", -240.0 / 60.0, 0.0, 36000, 0),
+                make_track("2025-02-13T10:00:00Z", -200.0 / 60.0, 0.0, 36000, 0),
+            ],
+        };
+        let estimate = EtaModel::default()
+            .estimate(&flight, &destination, Some(&history))
+            .unwrap();
+        assert!((estimate.cruise_speed_kt - 480.0).abs() < 1.0);
+
+        // Holding 30 nm out barely closes in, the ground speed is used instead
+        let holding = FullLiveFlightData {
+            timestamp: "2025-02-13T10:00:00Z".to_string(),
+            gspeed: 220,
+            ..make_position("c", -30.0 / 60.0, 0.0, 8000, 270)
+        };
+        let hold = Flight {
+            id: "c".to_string(),
+            tracks: vec![
+                make_track("2025-02-13T09:54:00Z", -31.0 / 60.0, 0.0, 8000, 90),
+                make_track("2025-02-13T09:57:00Z", -34.0 / 60.0, 0.02, 8000, 270),
+                make_track("2025-02-13T10:00:00Z", -30.0 / 60.0, 0.0, 8000, 270),
+            ],
+        };
+        let estimate = EtaModel::default()
+            .estimate(&holding, &destination, Some(&hold))
+            .unwrap();
+        assert_eq!(220.0, estimate.cruise_speed_kt);
+
+        // Timestamps are read like everywhere else in the crate
+        let spaced = FullLiveFlightData {
+            timestamp: "2025-02-13T10:00:00+0000".to_string(),
+            ..make_position("a", -200.0 / 60.0, 0.0, 36000, 0)
+        };
+        assert!(EtaModel::default()
+            .estimate(&spaced, &destination, None)
+            .is_ok());
+
+        let parked = FullLiveFlightData {
+            gspeed: 0,
+            timestamp: "2025-02-13T10:00:00Z".to_string(),
+            ..make_position("b", 1.0, 1.0, 0, 0)
+        };
+        assert!(EtaModel::default()
+            .estimate(&parked, &destination, None)
+            .is_err());
+    }
+
+    #[test]
+    fn check_historic_range_resume() {
+        let base = "http://127.0.0.1:9/";
+        let client_with = |timestamps: &[u64], params: &str| {
+            let cache = MemoryCache::new(16);
+            for timestamp in timestamps {
+                cache.put(
+                    &format!(
+                        "{}historic/flight-positions/full?timestamp={}&flights=SK123{}",
+                        base, timestamp, params
+                    ),
+                    &serde_json::to_string(&FullLiveFlightResponse {
+                        data: vec![FullLiveFlightData {
+                            fr24_id: "3a1".to_string(),
+                            ..FullLiveFlightData::default()
+                        }],
+                    })
+                    .unwrap(),
+                    None,
+                );
+            }
+            let mut client = FlightRadarClient::new("unused".to_string());
+            client.update_base_url(base.to_string());
+            client.set_cache(Box::new(cache), CachePolicy::default());
+            client
+        };
+        let query = FullLiveFlightQuery {
+            flights: Some(vec!["SK123".to_string()]),
+            ..FullLiveFlightQuery::default()
+        };
+        let (from, to) = (1739400000, 1739400150);
+        let path = std::env::temp_dir().join(format!("fr24-sweep-{}.json", std::process::id()));
+
+        // Third snapshot is unreachable, the sweep stops there
+        let client = client_with(&[from, from + 60], "");
+        let sweep = client
+            .historic_range(&query, from, to, 60)
+            .unwrap()
+            .with_checkpoint_file(&path)
+            .unwrap();
+        assert_eq!(3, sweep.remaining());
+        let results: Vec<_> = sweep.collect();
+        assert_eq!(3, results.len());
+        assert_eq!(from + 60, results[1].as_ref().unwrap().0);
+        assert!(results[2].is_err());
+        let checkpoint = SweepCheckpoint::load(&path).unwrap();
+        assert_eq!((from + 120, 2), (checkpoint.next, checkpoint.credits_used));
+
+        // Resuming only fetches what is left, counting earlier credits
+        let client = client_with(&[from, from + 60, from + 120], "");
+        let mut sweep = client
+            .historic_range(&query, from, to, 60)
+            .unwrap()
+            .with_checkpoint_file(&path)
+            .unwrap();
+        let (timestamp, snapshot) = sweep.next().unwrap().unwrap();
+        assert_eq!((from + 120, 1), (timestamp, snapshot.data.len()));
+        assert!(sweep.next().is_none());
+        assert_eq!(3, sweep.credits_used());
+        assert!(sweep.checkpoint().is_complete());
+
+        // Checkpoint of another sweep is refused
+        assert!(client
+            .historic_range(&query, from, to, 30)
+            .unwrap()
+            .with_checkpoint_file(&path)
+            .is_err());
+        let other = FullLiveFlightQuery {
+            flights: Some(vec!["SK124".to_string()]),
+            ..FullLiveFlightQuery::default()
+        };
+        assert!(client
+            .historic_range(&other, from, to, 60)
+            .unwrap()
+            .with_checkpoint_file(&path)
+            .is_err());
+        std::fs::remove_file(&path).unwrap();
+
+        // Requests ask for no more aircraft than the budget has left
+        let client = client_with(&[from], "&limit=1");
+        let results: Vec<_> = client
+            .historic_range(&query, from, to, 60)
+            .unwrap()
+            .with_credit_budget(1)
+            .collect();
+        assert_eq!(2, results.len());
+        assert!(results[0].is_ok() && results[1].is_err());
+
+        assert!(client
+            .historic_range(&query, MIN_TIMESTAMP - 1, to, 60)
+            .is_err());
+        assert!(client.historic_range(&query, to, from, 60).is_err());
+        assert!(client.historic_range(&query, from, to, 0).is_err());
+        assert!(client
+            .historic_range(&FullLiveFlightQuery::default(), from, to, 60)
+            .is_err());
+    }
+
+    #[test]
+    fn check_track_builder() {
+        let report = |id: &str, timestamp: &str, lon: f64, callsign: &str, squawk: &str| {
+            FullLiveFlightData {
+                timestamp: timestamp.to_string(),
+                callsign: callsign.to_string(),
+                squawk: squawk.to_string(),
+                vspeed: -800,
+                ..make_position(id, 50.0, lon, 10000, 90)
+            }
+        };
+        let snapshots = [
+            FullLiveFlightResponse {
+                data: vec![
+                    report("b", "2025-02-13T00:02:00Z", 10.2, "", "1000"),
+                    report("a", "2025-02-13T00:00:00Z", 1.0, "SAS1", "2000"),
+                ],
+            },
+            FullLiveFlightResponse {
+                data: vec![
+                    report("a", "2025-02-13T00:00:00Z", 9.9, "SAS1", "2000"),
+                    report("b", "2025-02-13T00:01:00Z", 10.1, "TEST1", ""),
+                    report("", "2025-02-13T00:01:00Z", 0.0, "", ""),
+                    report("c", "not a time", 0.0, "", ""),
+                ],
+            },
+            FullLiveFlightResponse {
+                data: vec![
+                    report("b", "2025-02-13T00:03:00Z", 10.3, "TEST2", "7700"),
+                    report("b", "2025-02-13T00:04:00Z", 10.4, "", ""),
+                ],
+            },
+        ];
+
+        let mut builder = TrackBuilder::new();
+        for snapshot in &snapshots {
+            builder.add_snapshot(snapshot);
+        }
+        assert_eq!((2, 2), (builder.len(), builder.skipped()));
+
+        let flights = builder.build();
+        assert_eq!("a", flights[0].flight.id);
+        assert_eq!(1, flights[0].flight.tracks.len());
+        assert_eq!(1.0, flights[0].flight.tracks[0].lon);
+        assert!(flights[0].callsign_changes.is_empty());
+
+        let b = &flights[1];
+        let lons: Vec<f64> = b.flight.tracks.iter().map(|t| t.lon).collect();
+        assert_eq!(vec![10.1, 10.2, 10.3, 10.4], lons);
+        assert_eq!(
+            vec!["TEST1", "TEST1", "TEST2", "TEST2"],
+            get_callsign_from_flight(std::slice::from_ref(&b.flight))
+        );
+        assert_eq!(
+            vec!["1000", "1000", "7700", "7700"],
+            get_squawk_from_flight(std::slice::from_ref(&b.flight))
+        );
+        assert_eq!(1, b.callsign_changes.len());
+        assert_eq!(
+            ("TEST1", "TEST2"),
+            (
+                b.callsign_changes[0].previous.as_str(),
+                b.callsign_changes[0].current.as_str()
+            )
+        );
+        assert_eq!(
+            "2025-02-13T00:03:00Z"
+                .parse::<chrono::DateTime<chrono::Utc>>()
+                .unwrap(),
+            b.squawk_changes[0].timestamp
+        );
+        // Descents keep their sign
+        assert_eq!(-800, b.flight.tracks[0].vspeed);
+    }
+
+    #[test]
+    fn check_track_smoothing() {
+        // 450 kt due east at 10 s intervals, 1.25 nm between points
+        let step = 1.25 / 60.0 / 50f64.to_radians().cos();
+        let point = |i: usize| Track {
+            gspeed: 450,
+            ..make_track(
+                &format!("2025-02-13T00:{:02}:{:02}Z", i / 6, i % 6 * 10),
+                50.0,
+                10.0 + i as f64 * step,
+                30000,
+                90,
+            )
+        };
+        let mut tracks: Vec<Track> = (0..12).map(point).collect();
+        // MLAT fix 0.5 nm north of the line, a jump of 1 degree, a duplicate
+        // timestamp and an unreadable one
+        tracks[4].lat += 0.5 / 60.0;
+        tracks[4].source = "MLAT".to_string();
+        tracks[7].lat += 1.0;
+        tracks.push(Track {
+            lat: 0.0,
+            ..point(2)
+        });
+        tracks.push(Track {
+            timestamp: "soon".to_string(),
+            ..point(3)
+        });
+        tracks.swap(0, 5);
+        let flight = Flight {
+            id: "390163bf".to_string(),
+            tracks,
+        };
+
+        let smoothed = flight.smooth();
+        assert_eq!(14, smoothed.flags.len());
+        assert_eq!(
+            vec![PointFlag::Rejected; 3],
+            vec![smoothed.flags[7], smoothed.flags[12], smoothed.flags[13]]
+        );
+        assert_eq!(PointFlag::Smoothed, smoothed.flags[4]);
+        assert_eq!(10, smoothed.count(PointFlag::Kept));
+
+        let points = &smoothed.flight.tracks;
+        assert_eq!(11, points.len());
+        assert!(points.windows(2).all(|w| w[0].timestamp < w[1].timestamp));
+        assert_eq!("MLAT", points[4].source);
+        assert!((points[4].lat - 50.0).abs() < 0.1 / 60.0);
+
+        // An outlier first point doesn't take the rest of the track with it
+        let mut tracks: Vec<Track> = (0..4).map(point).collect();
+        tracks[0].lon += 2.0;
+        let first = Flight {
+            id: "a".to_string(),
+            tracks,
+        }
+        .smooth();
+        assert_eq!(PointFlag::Rejected, first.flags[0]);
+        assert_eq!(3, first.count(PointFlag::Kept));
+
+        assert_eq!(1.0, source_noise_factor("adsb"));
+        assert!(source_noise_factor("ESTIMATED") > source_noise_factor("MLAT"));
+    }
+
+    #[test]
+    fn check_flight_events() {
+        let destination = Airport {
+            icao: "ESSA".to_string(),
+            lat: 50.0,
+            lon: 10.0,
+            elevation: 100,
+            ..Default::default()
+        };
+        let mut seconds = 0;
+        let mut point = |east_nm: f64, north_nm: f64, alt: u32, track: u32, gspeed: u32| {
+            seconds += 20;
+            let timestamp = chrono::DateTime::from_timestamp(1739440800 + seconds, 0).unwrap();
+            Track {
+                gspeed,
+                ..make_track(
+                    &timestamp.to_rfc3339(),
+                    50.0 + north_nm / 60.0,
+                    10.0 + east_nm / 60.0 / 50f64.to_radians().cos(),
+                    alt,
+                    track,
+                )
+            }
+        };
+
+        let mut tracks = Vec::new();
+        // Approach from the west, lowest at 1.5 nm then climb out
+        for (east, alt) in [(-8.0, 3000), (-5.0, 1500), (-3.0, 800), (-1.5, 400)] {
+            tracks.push(point(east, 0.0, alt, 90, 160));
+        }
+        for (east, alt) in [(-0.5, 700), (0.5, 1100), (2.0, 2000)] {
+            tracks.push(point(east, 0.0, alt, 90, 160));
+        }
+        // Two turns of a 2 nm radius circle 30 nm north, 30 degrees per point
+        for i in 0..=24 {
+            let angle = (i as f64 * 30.0).to_radians();
+            tracks.push(point(
+                2.0 * angle.sin(),
+                30.0 + 2.0 * angle.cos(),
+                6000,
+                (90 + i * 30) % 360,
+                220,
+            ));
+        }
+        // Landing 80 nm east of the destination
+        for (east, alt, gspeed) in [(78.0, 1000, 150), (80.0, 0, 120), (80.3, 0, 20)] {
+            tracks.push(point(east, 0.0, alt, 90, gspeed));
+        }
+        let flight = Flight {
+            id: "3a1".to_string(),
+            tracks,
+        };
+
+        let detector = EventDetector::default();
+        let go_arounds = detector.go_arounds(&flight, &destination);
+        assert_eq!(1, go_arounds.len());
+        assert_eq!(400, go_arounds[0].lowest.alt);
+        assert_eq!(1100, go_arounds[0].climb.alt);
+        assert!((go_arounds[0].distance_nm - 1.5).abs() < 0.05);
+
+        let holdings = detector.holdings(&flight);
+        assert_eq!(1, holdings.len());
+        assert_eq!(2, holdings[0].turns);
+        assert_eq!(460, holdings[0].duration().num_seconds());
+        assert!((holdings[0].center_lat - 50.5).abs() < 0.01);
+
+        let diversion = detector.diversion(&flight, &destination).unwrap();
+        assert_eq!("ESSA", diversion.planned);
+        assert_eq!(1739440800 + 680, diversion.landing.timestamp.timestamp());
+        assert!((diversion.distance_nm - 80.0).abs() < 0.5);
+
+        let events = flight.detect_events(&destination);
+        assert!(matches!(
+            events.as_slice(),
+            [
+                FlightEvent::GoAround(_),
+                FlightEvent::Holding(_),
+                FlightEvent::Diversion(_)
+            ]
+        ));
+
+        // Landing at the destination is no diversion
+        let landed = Flight {
+            id: "3a2".to_string(),
+            tracks: vec![point(-2.0, 0.0, 700, 90, 140), point(0.0, 0.0, 0, 90, 30)],
+        };
+        assert!(detector.diversion(&landed, &destination).is_none());
+        assert!(landed.detect_events(&destination).is_empty());
+    }
+
+    #[test]
+    fn check_runway_inference() {
+        let csv = "\"id\",\"airport_ref\",\"airport_ident\",\"length_ft\",\"width_ft\",\"surface\",\"lighted\",\"closed\",\"le_ident\",\"le_latitude_deg\",\"le_longitude_deg\",\"le_elevation_ft\",\"le_heading_degT\",\"le_displaced_threshold_ft\",\"he_ident\",\"he_latitude_deg\",\"he_longitude_deg\",\"he_elevation_ft\",\"he_heading_degT\",\"he_displaced_threshold_ft\"
+1,1,\"ESSA\",8200,148,\"ASP\",1,0,\"01L\",59.63,17.9,120,0.5,,\"19R\",59.67,17.9,130,180.5,
+2,1,\"essa\",,148,\"ASP\",1,0,\"01R\",59.63,17.93,110,,,\"19L\",59.67,17.93,120,,
+3,1,\"ESSA\",3000,98,\"ASP\",1,1,\"08\",59.65,17.85,120,80,,\"26\",59.65,17.9,120,260,
+4,2,\"ESSB\",5000,98,\"ASP\",1,0,\"12\",59.35,17.94,10,120,,\"30\",,,,,
+";
+        let database = RunwayDatabase::from_reader(csv.as_bytes()).unwrap();
+        assert_eq!(2, database.len());
+        let essa = database.airport("essa");
+        assert_eq!(4, essa.len());
+        let r19l = essa.iter().find(|r| r.ident == "19L").unwrap();
+        assert!((r19l.heading - 180.0).abs() < 0.01);
+        assert!((r19l.length_ft - 14585.0).abs() < 20.0);
+        assert_eq!(1, database.airport("ESSB").len());
+        assert!(RunwayDatabase::from_reader("airport_ident\n\"a\",\"b\"".as_bytes()).is_err());
+
+        let mut seconds = 0;
+        let mut point = |lat: f64, lon: f64, alt: u32, gspeed: u32, track: u32| {
+            seconds += 15;
+            let timestamp = chrono::DateTime::from_timestamp(1739440800 + seconds, 0).unwrap();
+            Track {
+                gspeed,
+                ..make_track(&timestamp.to_rfc3339(), lat, lon, alt, track)
+            }
+        };
+
+        // Southbound approach slightly west of the 19L centreline, roll out and taxi
+        let lon = 17.93 - 0.05 / 60.0 / 59.65f64.to_radians().cos();
+        let mut tracks: Vec<Track> = [(6.0, 1900), (4.0, 1300), (2.0, 700), (0.3, 200)]
+            .into_iter()
+            .map(|(nm, alt)| point(59.67 + nm / 60.0, lon, alt, 150, 180))
+            .collect();
+        tracks.push(point(59.66, lon, 0, 100, 180));
+        tracks.push(point(59.655, lon, 0, 40, 181));
+        tracks.push(point(59.655, 17.92, 0, 15, 270));
+        let arrival = Flight {
+            id: "3a1".to_string(),
+            tracks,
+        };
+        let landing = database.infer_landing(&arrival, "ESSA").unwrap();
+        assert_eq!("19L", landing.runway.ident);
+        assert_eq!(RunwayOperation::Landing, landing.operation);
+        assert_eq!(6, landing.matched_points);
+        assert!((landing.mean_offset_nm - 0.05).abs() < 0.01);
+        assert!(database.infer_departure(&arrival, "ESSA").is_none());
+
+        // Northbound take-off from 01L, the last point is above the height limit
+        let tracks = vec![
+            point(59.625, 17.89, 0, 10, 90),
+            point(59.63, 17.9, 0, 20, 0),
+            point(59.64, 17.9, 0, 120, 0),
+            point(59.66, 17.9, 800, 160, 1),
+            point(59.70, 17.9, 2000, 180, 359),
+            point(59.75, 17.9, 4000, 200, 0),
+        ];
+        let departure = Flight {
+            id: "3a2".to_string(),
+            tracks,
+        };
+        let usage = database.infer_departure(&departure, "ESSA").unwrap();
+        assert_eq!("01L", usage.runway.ident);
+        assert_eq!(4, usage.matched_points);
+        assert!(database.infer_departure(&departure, "ESSB").is_none());
+        assert!(database.infer_departure(&departure, "XXXX").is_none());
+    }
+
+    #[test]
+    fn check_track_columns() {
+        let flights = vec![
+            Flight {
+                id: "3a1".to_string(),
+                tracks: vec![
+                    make_track("2025-02-13T00:00:00Z", 50.0, 10.0, 10000, 90),
+                    make_track("2025-02-13T00:01:00Z", 50.0, 11.0, 12000, 95),
+                ],
+            },
+            Flight {
+                id: "3a2".to_string(),
+                tracks: Vec::new(),
+            },
+            Flight {
+                id: "3a3".to_string(),
+                tracks: vec![Track {
+                    squawk: "7700".to_string(),
+                    ..make_track("2025-02-13T00:02:00Z", 40.0, 5.0, 3000, 180)
+                }],
+            },
+        ];
+
+        let points: Vec<(&str, u32)> = flights.points().map(|(id, t)| (id, t.alt)).collect();
+        assert_eq!(vec![("3a1", 10000), ("3a1", 12000), ("3a3", 3000)], points);
+
+        let alts = flights.alts();
+        assert_eq!(3, alts.len());
+        assert_eq!(
+            ("3a1", vec![10000, 12000]),
+            (alts[0].fr24_id, alts[0].values.clone())
+        );
+        assert!(alts[1].values.is_empty());
+        assert_eq!(vec!["7700"], flights.squawks()[2].values);
+        assert_eq!(vec![90, 95], flights.headings()[0].values);
+        assert_eq!(vec![10.0, 11.0], flights.column(|t| t.lon)[0].values);
+        let timestamps = flights.timestamps().unwrap();
+        assert_eq!(
+            60,
+            (timestamps[0].values[1] - timestamps[0].values[0]).num_seconds()
+        );
+
+        let columns = flights.columns();
+        assert_eq!(
+            vec![2, 0, 1],
+            columns.iter().map(|c| c.len()).collect::<Vec<_>>()
+        );
+        assert_eq!(flights[2].columns(), columns[2]);
+        assert_eq!(vec![40.0], columns[2].lat);
+
+        // Flat helpers stay as they were
+        assert_eq!(vec![10000, 12000, 3000], get_alt_from_flight(&flights));
+        assert_eq!(
+            get_squawk_from_flight(&flights),
+            get_squack_from_flight(&flights)
+        );
+        assert_eq!(
+            "2025-02-13 00:02:00 UTC",
+            get_timestamps_from_flight(&flights)[2]
+        );
+
+        let mut broken = flights.clone();
+        broken[2].tracks[0].timestamp = "later".to_string();
+        assert!(broken.timestamps().is_err());
+    }
 }